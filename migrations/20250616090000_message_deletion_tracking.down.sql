@@ -0,0 +1,5 @@
+-- Remove message deletion tracking columns
+ALTER TABLE message_logs
+    DROP COLUMN IF EXISTS deleted_by,
+    DROP COLUMN IF EXISTS deleted_at,
+    DROP COLUMN IF EXISTS deleted;
@@ -0,0 +1,5 @@
+-- Track messages removed by moderation (e.g. /purge)
+ALTER TABLE message_logs
+    ADD COLUMN IF NOT EXISTS deleted BOOLEAN DEFAULT FALSE,
+    ADD COLUMN IF NOT EXISTS deleted_at DATETIME,
+    ADD COLUMN IF NOT EXISTS deleted_by BIGINT;
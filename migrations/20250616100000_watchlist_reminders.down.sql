@@ -0,0 +1,4 @@
+-- Remove watchlist reminder support
+ALTER TABLE user_watchlist
+    DROP INDEX IF EXISTS idx_remind_at,
+    DROP COLUMN IF EXISTS remind_at;
@@ -0,0 +1,4 @@
+-- Reminder timestamps for personal watchlist items
+ALTER TABLE user_watchlist
+    ADD COLUMN IF NOT EXISTS remind_at DATETIME,
+    ADD INDEX IF NOT EXISTS idx_remind_at (remind_at);
@@ -0,0 +1,4 @@
+-- Remove event reminder tracking
+ALTER TABLE event_logs
+    DROP COLUMN IF EXISTS reminded,
+    DROP INDEX IF EXISTS unique_event_id;
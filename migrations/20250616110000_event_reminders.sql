@@ -0,0 +1,8 @@
+-- Collapse duplicate event rows so event_id can be upserted on
+DELETE older FROM event_logs older
+JOIN event_logs newer ON older.event_id = newer.event_id AND older.id < newer.id;
+
+-- Make event_id unique and track whether interested users were reminded
+ALTER TABLE event_logs
+    ADD UNIQUE KEY IF NOT EXISTS unique_event_id (event_id),
+    ADD COLUMN IF NOT EXISTS reminded BOOLEAN DEFAULT FALSE;
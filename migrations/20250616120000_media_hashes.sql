@@ -0,0 +1,10 @@
+-- Track content hashes of stored media so identical files aren't written twice
+CREATE TABLE IF NOT EXISTS media_hashes (
+    id INT PRIMARY KEY AUTO_INCREMENT,
+    content_hash CHAR(64) NOT NULL,
+    file_path VARCHAR(500) NOT NULL,
+    size BIGINT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE KEY unique_file_path (file_path),
+    INDEX idx_content_hash (content_hash)
+);
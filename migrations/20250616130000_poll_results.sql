@@ -0,0 +1,11 @@
+-- Final per-answer vote tallies, kept after poll_votes rows are cleaned up
+CREATE TABLE IF NOT EXISTS poll_results (
+    id INT PRIMARY KEY AUTO_INCREMENT,
+    poll_id VARCHAR(255) NOT NULL,
+    answer_id INT NOT NULL,
+    answer_text TEXT,
+    vote_count INT NOT NULL DEFAULT 0,
+    recorded_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE KEY unique_poll_answer (poll_id, answer_id),
+    INDEX idx_poll_id (poll_id)
+);
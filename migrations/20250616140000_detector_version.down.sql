@@ -0,0 +1,4 @@
+-- Remove detector version tracking
+ALTER TABLE media_recommendations
+    DROP INDEX IF EXISTS idx_detector_version,
+    DROP COLUMN IF EXISTS detector_version;
@@ -0,0 +1,5 @@
+-- Record which MediaDetector revision produced each recommendation, so detections
+-- from older pattern sets can be purged and rescanned
+ALTER TABLE media_recommendations
+    ADD COLUMN IF NOT EXISTS detector_version INT NOT NULL DEFAULT 1,
+    ADD INDEX IF NOT EXISTS idx_detector_version (detector_version);
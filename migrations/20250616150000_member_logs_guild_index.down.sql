@@ -0,0 +1,3 @@
+-- Remove the per-guild churn index
+ALTER TABLE member_logs
+    DROP INDEX IF EXISTS idx_guild_timestamp;
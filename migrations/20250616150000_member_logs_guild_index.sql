@@ -0,0 +1,3 @@
+-- member_logs is now written on every join/leave; index it for per-guild churn queries
+ALTER TABLE member_logs
+    ADD INDEX IF NOT EXISTS idx_guild_timestamp (guild_id, timestamp);
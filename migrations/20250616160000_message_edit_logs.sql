@@ -0,0 +1,12 @@
+-- One row per message edit so the full edit chain survives, not just the latest content
+CREATE TABLE IF NOT EXISTS message_edit_logs (
+    id INT PRIMARY KEY AUTO_INCREMENT,
+    message_id BIGINT NOT NULL,
+    old_content TEXT,
+    new_content TEXT,
+    attachments TEXT,
+    embed_count INT,
+    edited_at DATETIME NOT NULL,
+    INDEX idx_message_id (message_id),
+    INDEX idx_edited_at (edited_at)
+);
@@ -0,0 +1,4 @@
+-- Remove per-channel thread tracking
+ALTER TABLE forum_logs
+    DROP INDEX IF EXISTS idx_guild_created,
+    DROP COLUMN IF EXISTS parent_id;
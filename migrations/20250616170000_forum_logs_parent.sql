@@ -0,0 +1,4 @@
+-- Record which channel/forum a thread was started in, for per-channel thread stats
+ALTER TABLE forum_logs
+    ADD COLUMN IF NOT EXISTS parent_id BIGINT NULL AFTER guild_id,
+    ADD INDEX IF NOT EXISTS idx_guild_created (guild_id, created_at);
@@ -0,0 +1,4 @@
+-- Remove global watchlist completion tracking
+ALTER TABLE global_watchlist
+    DROP INDEX IF EXISTS idx_completed_at,
+    DROP COLUMN IF EXISTS completed_at;
@@ -0,0 +1,4 @@
+-- Track when the community finished a global watchlist item
+ALTER TABLE global_watchlist
+    ADD COLUMN IF NOT EXISTS completed_at DATETIME NULL AFTER added_at,
+    ADD INDEX IF NOT EXISTS idx_completed_at (completed_at);
@@ -0,0 +1,3 @@
+-- Remove the global watchlist promotion flag
+ALTER TABLE global_watchlist
+    DROP COLUMN IF EXISTS promoted;
@@ -0,0 +1,3 @@
+-- One-time flag so a global watchlist item's vote milestone is only announced once
+ALTER TABLE global_watchlist
+    ADD COLUMN IF NOT EXISTS promoted BOOLEAN NOT NULL DEFAULT FALSE AFTER completed_at;
@@ -0,0 +1,2 @@
+-- Remove the per-user command cooldowns table
+DROP TABLE IF EXISTS command_cooldowns;
@@ -0,0 +1,7 @@
+-- Per-user cooldowns for rate-limited fun commands (/gif, /meme random)
+CREATE TABLE IF NOT EXISTS command_cooldowns (
+    user_id BIGINT NOT NULL,
+    command VARCHAR(64) NOT NULL,
+    last_used DATETIME NOT NULL,
+    PRIMARY KEY (user_id, command)
+);
@@ -0,0 +1,5 @@
+-- Restore the dedicated snort cooldown table
+CREATE TABLE IF NOT EXISTS user_snort_cooldowns (
+    user_id BIGINT PRIMARY KEY,
+    last_snort_time DATETIME NOT NULL
+);
@@ -0,0 +1,2 @@
+-- /snort cooldowns now live in command_cooldowns under the 'snort' key
+DROP TABLE IF EXISTS user_snort_cooldowns;
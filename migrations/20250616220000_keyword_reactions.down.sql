@@ -0,0 +1,2 @@
+-- Remove keyword auto-reactions
+DROP TABLE IF EXISTS keyword_reactions;
@@ -0,0 +1,10 @@
+-- Per-guild keyword -> emoji auto-reactions managed with /reactions
+CREATE TABLE IF NOT EXISTS keyword_reactions (
+    id INT PRIMARY KEY AUTO_INCREMENT,
+    guild_id BIGINT NOT NULL,
+    keyword VARCHAR(100) NOT NULL,
+    emoji VARCHAR(100) NOT NULL,
+    added_by BIGINT NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE KEY uq_keyword_reactions_guild_keyword (guild_id, keyword)
+);
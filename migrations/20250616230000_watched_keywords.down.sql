@@ -0,0 +1,2 @@
+-- Remove moderator watchwords
+DROP TABLE IF EXISTS watched_keywords;
@@ -0,0 +1,10 @@
+-- Per-guild watchwords that raise a mod alert when posted, managed with /keywords
+CREATE TABLE IF NOT EXISTS watched_keywords (
+    id INT PRIMARY KEY AUTO_INCREMENT,
+    guild_id BIGINT NOT NULL,
+    keyword VARCHAR(100) NOT NULL,
+    severity VARCHAR(16) NOT NULL DEFAULT 'medium',
+    added_by BIGINT NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE KEY uq_watched_keywords_guild_keyword (guild_id, keyword)
+);
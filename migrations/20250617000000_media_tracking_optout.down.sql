@@ -0,0 +1,2 @@
+-- Remove media tracking opt-outs
+DROP TABLE IF EXISTS media_tracking_optout;
@@ -0,0 +1,5 @@
+-- Users who opted out of media recommendation tracking with /privacy optout
+CREATE TABLE IF NOT EXISTS media_tracking_optout (
+    user_id BIGINT PRIMARY KEY,
+    opted_out_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,5 @@
+-- State-change rows can't be represented by the old enum, so drop them first
+DELETE FROM voice_logs WHERE action NOT IN ('join', 'leave', 'switch');
+
+ALTER TABLE voice_logs
+    MODIFY action ENUM('join', 'leave', 'switch') NOT NULL;
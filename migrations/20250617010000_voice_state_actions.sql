@@ -0,0 +1,8 @@
+-- Record mute, deafen and streaming transitions alongside join/leave/switch
+ALTER TABLE voice_logs
+    MODIFY action ENUM(
+        'join', 'leave', 'switch',
+        'self_mute', 'self_unmute', 'self_deaf', 'self_undeaf',
+        'server_mute', 'server_unmute', 'server_deaf', 'server_undeaf',
+        'stream_start', 'stream_stop'
+    ) NOT NULL;
@@ -0,0 +1,2 @@
+-- Remove the Steam app name cache
+DROP TABLE IF EXISTS steam_app_cache;
@@ -0,0 +1,6 @@
+-- Steam app names looked up from the store API, keyed by app ID
+CREATE TABLE IF NOT EXISTS steam_app_cache (
+    app_id BIGINT PRIMARY KEY,
+    name VARCHAR(500) NOT NULL,
+    fetched_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
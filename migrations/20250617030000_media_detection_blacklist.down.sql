@@ -0,0 +1,2 @@
+-- Remove the media detection blacklist
+DROP TABLE IF EXISTS media_detection_blacklist;
@@ -0,0 +1,8 @@
+-- Titles that media detection should never record, managed with /mediaconfig blacklist
+CREATE TABLE IF NOT EXISTS media_detection_blacklist (
+    id INT PRIMARY KEY AUTO_INCREMENT,
+    term VARCHAR(255) NOT NULL,
+    added_by BIGINT NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE KEY uq_media_detection_blacklist_term (term)
+);
@@ -0,0 +1,4 @@
+-- Drop the normalized title grouping key
+ALTER TABLE media_recommendations
+    DROP INDEX idx_media_type_normalized_title,
+    DROP COLUMN normalized_title;
@@ -0,0 +1,18 @@
+-- Group spelling variants of a title ("Jujutsu Kaisen", "jujutsu kaisen!") under one key.
+-- The backfill mirrors media_detector::normalize_title: apostrophes are dropped, other
+-- punctuation becomes a space, whitespace is collapsed and the result lowercased.
+ALTER TABLE media_recommendations
+    ADD COLUMN normalized_title VARCHAR(500) NULL AFTER title,
+    ADD INDEX idx_media_type_normalized_title (media_type, normalized_title);
+
+UPDATE media_recommendations
+SET normalized_title = LOWER(TRIM(
+    REGEXP_REPLACE(
+        REGEXP_REPLACE(
+            REGEXP_REPLACE(title, '[''‘’]', ''),
+            '[!-/:-@\\[-`\\{-~“”]', ' '
+        ),
+        '[[:space:]]+', ' '
+    )
+))
+WHERE title IS NOT NULL;
@@ -0,0 +1,2 @@
+-- Backfilled guild IDs are correct data; nothing to undo
+SELECT 1;
@@ -0,0 +1,24 @@
+-- message_logs.guild_id was never written. Fill it in from tables that already map
+-- channels to guilds; /serverinfo assigns any remaining channels from the cache.
+UPDATE message_logs ml
+JOIN channel_scan_history csh ON csh.channel_id = ml.channel_id
+SET ml.guild_id = csh.guild_id
+WHERE ml.guild_id IS NULL;
+
+UPDATE message_logs ml
+JOIN (
+    SELECT channel_id, MAX(guild_id) AS guild_id
+    FROM media_recommendations
+    GROUP BY channel_id
+) mr ON mr.channel_id = ml.channel_id
+SET ml.guild_id = mr.guild_id
+WHERE ml.guild_id IS NULL;
+
+UPDATE message_logs ml
+JOIN (
+    SELECT channel_id, MAX(guild_id) AS guild_id
+    FROM channel_logs
+    GROUP BY channel_id
+) cl ON cl.channel_id = ml.channel_id
+SET ml.guild_id = cl.guild_id
+WHERE ml.guild_id IS NULL;
@@ -0,0 +1,239 @@
+use anyhow::Result;
+use axum::extract::{Query, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::env;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tracing::{error, info, warn};
+
+use crate::db::Database;
+
+const DEFAULT_BIND_ADDR: &str = "127.0.0.1:8080";
+
+/// Most rows a single request can ask for.
+const MAX_LIMIT: u32 = 100;
+
+/// Settings for the read-only HTTP API, taken from the environment.
+pub struct ApiConfig {
+    pub bind_addr: SocketAddr,
+    pub token: String,
+}
+
+impl ApiConfig {
+    /// Returns `None` unless `ENABLE_HTTP_API` is set. The API refuses to start without
+    /// `HTTP_API_TOKEN`, so it can never be exposed unauthenticated by accident.
+    pub fn from_env() -> Option<Self> {
+        let enabled = env::var("ENABLE_HTTP_API")
+            .map(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+            .unwrap_or(false);
+        if !enabled {
+            return None;
+        }
+
+        let token = match env::var("HTTP_API_TOKEN") {
+            Ok(token) if !token.trim().is_empty() => token.trim().to_string(),
+            _ => {
+                warn!("ENABLE_HTTP_API is set but HTTP_API_TOKEN is missing - HTTP API disabled");
+                return None;
+            }
+        };
+
+        let bind = env::var("HTTP_API_BIND").unwrap_or_else(|_| DEFAULT_BIND_ADDR.to_string());
+        let bind_addr = match bind.parse() {
+            Ok(addr) => addr,
+            Err(_) => {
+                warn!("Invalid HTTP_API_BIND '{}' - HTTP API disabled", bind);
+                return None;
+            }
+        };
+
+        Some(Self { bind_addr, token })
+    }
+}
+
+#[derive(Clone)]
+struct ApiState {
+    db: Database,
+    token: Arc<str>,
+}
+
+/// Serves the API until the listener fails. `/api/health` is open so load balancers
+/// can probe it; everything else needs `Authorization: Bearer <HTTP_API_TOKEN>`.
+pub async fn serve(db: Database, config: ApiConfig) -> Result<()> {
+    let state = ApiState {
+        db,
+        token: config.token.into(),
+    };
+
+    let protected = Router::new()
+        .route("/api/recommendations/top", get(top_recommendations))
+        .route("/api/global", get(global_watchlist))
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_token));
+
+    let app = Router::new()
+        .route("/api/health", get(health))
+        .merge(protected)
+        .with_state(state);
+
+    let listener = tokio::net::TcpListener::bind(config.bind_addr).await?;
+    info!("HTTP API listening on {}", config.bind_addr);
+    axum::serve(listener, app).await?;
+
+    Ok(())
+}
+
+async fn require_token(State(state): State<ApiState>, request: Request, next: Next) -> Response {
+    let provided = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .unwrap_or("");
+
+    if constant_time_eq(provided.as_bytes(), state.token.as_bytes()) {
+        next.run(request).await
+    } else {
+        (
+            StatusCode::UNAUTHORIZED,
+            Json(json!({ "error": "missing or invalid bearer token" })),
+        )
+            .into_response()
+    }
+}
+
+// Compare without bailing at the first differing byte so timing doesn't leak the token
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn internal_error(context: &str, e: anyhow::Error) -> Response {
+    error!("HTTP API failed to {}: {}", context, e);
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(json!({ "error": format!("failed to {}", context) })),
+    )
+        .into_response()
+}
+
+async fn health(State(state): State<ApiState>) -> Response {
+    match state.db.ping().await {
+        Ok(()) => Json(json!({ "status": "ok", "database": "ok" })).into_response(),
+        Err(e) => {
+            warn!("HTTP API health check failed: {}", e);
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(json!({ "status": "degraded", "database": "unreachable" })),
+            )
+                .into_response()
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct TopRecommendationsQuery {
+    limit: Option<u32>,
+    days: Option<i32>,
+    media_type: Option<String>,
+    min_mentions: Option<u32>,
+}
+
+async fn top_recommendations(
+    State(state): State<ApiState>,
+    Query(query): Query<TopRecommendationsQuery>,
+) -> Response {
+    let limit = query.limit.unwrap_or(10).clamp(1, MAX_LIMIT);
+    let days = query.days.unwrap_or(7).clamp(1, 365);
+    let min_mentions = query.min_mentions.unwrap_or(2);
+
+    match state
+        .db
+        .get_top_recommendations(limit, days, query.media_type.as_deref(), min_mentions)
+        .await
+    {
+        Ok(items) => {
+            let items: Vec<Value> = items
+                .into_iter()
+                .map(|(media_type, title, confidence, mentions, url)| {
+                    json!({
+                        "type": media_type,
+                        "title": title,
+                        "url": url,
+                        "confidence": confidence,
+                        "mentions": mentions
+                    })
+                })
+                .collect();
+            Json(json!({ "recommendations": items, "period_days": days })).into_response()
+        }
+        Err(e) => internal_error("load recommendations", e),
+    }
+}
+
+#[derive(Deserialize)]
+struct GlobalWatchlistQuery {
+    limit: Option<u32>,
+    #[serde(rename = "type")]
+    media_type: Option<String>,
+    include_completed: Option<bool>,
+}
+
+async fn global_watchlist(
+    State(state): State<ApiState>,
+    Query(query): Query<GlobalWatchlistQuery>,
+) -> Response {
+    let limit = query.limit.unwrap_or(25).clamp(1, MAX_LIMIT);
+
+    match state
+        .db
+        .get_global_watchlist(
+            limit,
+            query.media_type.as_deref(),
+            query.include_completed.unwrap_or(false),
+        )
+        .await
+    {
+        Ok(items) => {
+            let items: Vec<Value> = items
+                .into_iter()
+                .map(
+                    |(
+                        id,
+                        media_type,
+                        title,
+                        url,
+                        description,
+                        upvotes,
+                        downvotes,
+                        added_by,
+                        added_by_id,
+                        added_at,
+                        completed_at,
+                    )| {
+                        json!({
+                            "id": id,
+                            "type": media_type,
+                            "title": title,
+                            "url": url,
+                            "description": description,
+                            "upvotes": upvotes,
+                            "downvotes": downvotes,
+                            "net_votes": upvotes - downvotes,
+                            "added_by": added_by,
+                            "added_by_id": added_by_id.to_string(),
+                            "added_at": added_at.to_rfc3339(),
+                            "completed_at": completed_at.map(|t| t.to_rfc3339())
+                        })
+                    },
+                )
+                .collect();
+            Json(json!({ "global_watchlist": items })).into_response()
+        }
+        Err(e) => internal_error("load the global watchlist", e),
+    }
+}
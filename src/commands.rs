@@ -1,8 +1,45 @@
 use crate::db::Database;
+use crate::{i18n, moderation};
 use anyhow::Result;
-use serenity::all::{Colour, Context, CreateEmbed, CreateMessage, EditMember, Message, UserId};
+use serenity::all::{Colour, Context, CreateEmbed, CreateMessage, GuildId, Message, UserId};
+use std::collections::HashMap;
 use tracing::{error, info};
 
+/// Maximum edit distance accepted when fuzzy matching a user handle. Short handles get
+/// less slack, since one edit turns "bob" into "tom": none below 5 characters, one up to
+/// 8, and two beyond that.
+fn fuzzy_match_threshold(handle: &str) -> usize {
+    match handle.chars().count() {
+        0..=4 => 0,
+        5..=8 => 1,
+        _ => 2,
+    }
+}
+
+/// Commands handled by `handle_dm_command`, matched against typos in `suggest_command`.
+const DM_COMMANDS: &[&str] = &["/help", "/kick", "/ban", "/timeout", "/cache", "/whitelist"];
+
+/// Maximum edit distance for suggesting a DM command, enough for one transposed pair.
+const COMMAND_SUGGESTION_THRESHOLD: usize = 2;
+
+/// Levenshtein edit distance between two strings, counted in characters.
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b_chars: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b_chars.len()).collect();
+    let mut current = vec![0; b_chars.len() + 1];
+
+    for (i, a_char) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, b_char) in b_chars.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b_chars.len()]
+}
+
 pub struct CommandHandler {
     db: Database,
 }
@@ -87,11 +124,29 @@ impl CommandHandler {
         Ok(())
     }
 
+    /// Resolves a user ID, mention, username or server nickname exactly. Moderation and
+    /// whitelist commands use this so a typo can never land on someone else; the "not
+    /// found" reply lists close matches instead.
     pub async fn find_user_by_handle(
         &self,
         ctx: &Context,
         handle: &str,
     ) -> Option<(UserId, String)> {
+        // Raw user IDs (or <@id> mentions) can be resolved even if the user isn't cached
+        let raw_id = handle
+            .trim()
+            .trim_start_matches("<@")
+            .trim_start_matches('!')
+            .trim_end_matches('>');
+        if let Ok(id) = raw_id.parse::<u64>() {
+            if id != 0 {
+                match UserId::new(id).to_user(ctx).await {
+                    Ok(user) => return Some((user.id, user.tag())),
+                    Err(e) => info!("Could not resolve user ID {}: {}", id, e),
+                }
+            }
+        }
+
         // Remove @ prefix if present
         let handle = handle.strip_prefix('@').unwrap_or(handle);
 
@@ -122,6 +177,116 @@ impl CommandHandler {
         None
     }
 
+    /// Like [`Self::find_user_by_handle`], then falls back to the closest fuzzy match if it
+    /// is unambiguous. Only for read-only lookups, where a wrong guess is harmless.
+    pub async fn find_user_by_handle_fuzzy(
+        &self,
+        ctx: &Context,
+        handle: &str,
+    ) -> Option<(UserId, String)> {
+        if let Some(found) = self.find_user_by_handle(ctx, handle).await {
+            return Some(found);
+        }
+
+        let handle = handle.strip_prefix('@').unwrap_or(handle);
+        let candidates = self.fuzzy_user_candidates(ctx, handle);
+        match candidates.as_slice() {
+            [(distance, user_id, tag), rest @ ..]
+                if *distance <= fuzzy_match_threshold(handle)
+                    && rest.first().map(|(d, _, _)| d > distance).unwrap_or(true) =>
+            {
+                info!(
+                    "Fuzzy matched handle '{}' to {} (distance {})",
+                    handle, tag, distance
+                );
+                Some((*user_id, tag.clone()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Ranks cached members by edit distance between `handle` and their username,
+    /// global display name or server nickname, closest first.
+    fn fuzzy_user_candidates(&self, ctx: &Context, handle: &str) -> Vec<(usize, UserId, String)> {
+        let needle = handle.to_lowercase();
+        let mut best: HashMap<UserId, (usize, String)> = HashMap::new();
+
+        for guild_id in ctx.cache.guilds() {
+            if let Some(guild) = ctx.cache.guild(guild_id) {
+                for (user_id, member) in &guild.members {
+                    let user = &member.user;
+                    let distance = [
+                        Some(user.name.as_str()),
+                        user.global_name.as_deref(),
+                        member.nick.as_deref(),
+                    ]
+                    .into_iter()
+                    .flatten()
+                    .map(|name| levenshtein(&needle, &name.to_lowercase()))
+                    .min()
+                    .unwrap_or(usize::MAX);
+
+                    let entry = best
+                        .entry(*user_id)
+                        .or_insert_with(|| (distance, user.tag()));
+                    if distance < entry.0 {
+                        entry.0 = distance;
+                    }
+                }
+            }
+        }
+
+        let mut candidates: Vec<(usize, UserId, String)> = best
+            .into_iter()
+            .map(|(user_id, (distance, tag))| (distance, user_id, tag))
+            .collect();
+        candidates.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.2.cmp(&b.2)));
+        candidates
+    }
+
+    /// Builds the "user not found" reply, listing close matches so the caller can pick one.
+    pub fn user_not_found_message(&self, ctx: &Context, handle: &str) -> String {
+        let handle = handle.strip_prefix('@').unwrap_or(handle);
+        let suggestions: Vec<String> = self
+            .fuzzy_user_candidates(ctx, handle)
+            .into_iter()
+            .take_while(|(distance, _, _)| *distance <= fuzzy_match_threshold(handle) + 1)
+            .take(5)
+            .map(|(_, _, tag)| tag)
+            .collect();
+
+        let mut message = format!(
+            "User '{}' not found. Please use their username, @handle, server nickname, or user ID.",
+            handle
+        );
+        if !suggestions.is_empty() {
+            message.push_str(&format!(" Did you mean: {}?", suggestions.join(", ")));
+        }
+        message
+    }
+
+    pub fn find_guild(&self, ctx: &Context, query: &str) -> Option<GuildId> {
+        let query = query.trim();
+
+        // Match by guild ID first
+        if let Ok(id) = query.parse::<u64>() {
+            if id != 0 {
+                let guild_id = GuildId::new(id);
+                if ctx.cache.guilds().contains(&guild_id) {
+                    return Some(guild_id);
+                }
+            }
+        }
+
+        // Fall back to a case-insensitive name match
+        ctx.cache.guilds().into_iter().find(|guild_id| {
+            ctx.cache
+                .guild(*guild_id)
+                .map(|guild| guild.name.eq_ignore_ascii_case(query))
+                .unwrap_or(false)
+        })
+    }
+
     pub async fn handle_dm_command(&self, ctx: &Context, msg: &Message) -> Result<()> {
         let content = msg.content.trim();
         let parts: Vec<&str> = content.split_whitespace().collect();
@@ -141,7 +306,7 @@ impl CommandHandler {
             "/whitelist" => self.handle_whitelist(ctx, msg, &parts[1..]).await?,
             _ => {
                 // Suggest the most appropriate command
-                let suggestion = self.suggest_command(&command);
+                let suggestion = Self::suggest_command(&command);
                 let mut response = format!("Unknown command: '{}'\n\n", parts[0]);
 
                 if let Some(suggested) = suggestion {
@@ -158,7 +323,8 @@ impl CommandHandler {
         Ok(())
     }
 
-    fn suggest_command(&self, input: &str) -> Option<&'static str> {
+    fn suggest_command(input: &str) -> Option<&'static str> {
+        // Synonyms edit distance can't find, e.g. "mute" for /timeout
         let commands = vec![
             ("/help", vec!["help", "halp", "hlp", "h", "?"]),
             ("/kick", vec!["kick", "kik", "remove"]),
@@ -177,6 +343,19 @@ impl CommandHandler {
             }
         }
 
+        // Closest real command, so new typos like "kcik" resolve without another alias.
+        // Ties go to the earlier command in DM_COMMANDS.
+        let closest = DM_COMMANDS
+            .iter()
+            .map(|command| (levenshtein(&input_lower, &command[1..]), *command))
+            .min_by_key(|(distance, _)| *distance);
+        if let Some((distance, command)) = closest {
+            // A distance as long as the input itself means nothing was shared
+            if distance <= COMMAND_SUGGESTION_THRESHOLD && distance < input_lower.chars().count() {
+                return Some(command);
+            }
+        }
+
         // Check for partial matches at the beginning
         for (command, aliases) in &commands {
             if aliases
@@ -187,8 +366,8 @@ impl CommandHandler {
             }
         }
 
-        // Default to help if no match found
-        Some("/help")
+        // Nothing close enough; the reply already points at /help
+        None
     }
 
     async fn handle_help(&self, ctx: &Context, msg: &Message, _args: &[&str]) -> Result<()> {
@@ -272,99 +451,32 @@ impl CommandHandler {
         };
 
         if let Some((user_id, user_tag)) = self.find_user_by_handle(ctx, user_handle).await {
-            let guilds = ctx.cache.guilds();
-            let mut kicked_from = Vec::new();
-            let mut failed_guilds = Vec::new();
-
-            for guild_id in guilds {
-                // Check if the user is in this guild
-                let is_member = ctx
-                    .cache
-                    .guild(guild_id)
-                    .map(|guild| guild.members.contains_key(&user_id))
-                    .unwrap_or(false);
-
-                if is_member {
-                    let result = if let Some(reason) = reason.as_deref() {
-                        guild_id.kick_with_reason(&ctx.http, user_id, reason).await
-                    } else {
-                        guild_id.kick(&ctx.http, user_id).await
-                    };
-
-                    match result {
-                        Ok(_) => {
-                            // Get guild name from cache
-                            let guild_name = ctx
-                                .cache
-                                .guild(guild_id)
-                                .map(|g| g.name.clone())
-                                .unwrap_or_else(|| "Unknown".to_string());
-
-                            info!(
-                                "[MOD ACTION] {} kicked user {} ({}) from guild {} ({}) - reason: {}",
-                                msg.author.id,
-                                user_tag,
-                                user_id,
-                                guild_name,
-                                guild_id,
-                                reason.as_deref().unwrap_or("none")
-                            );
-                            kicked_from.push(guild_id);
-                        }
-                        Err(e) => {
-                            failed_guilds.push((guild_id, e.to_string()));
-                        }
-                    }
-                }
-            }
-
-            let mut response = String::new();
-            if !kicked_from.is_empty() {
-                let guild_names: Vec<String> = kicked_from
-                    .iter()
-                    .map(|g| {
-                        ctx.cache
-                            .guild(*g)
-                            .map(|guild| format!("{} ({})", guild.name, g))
-                            .unwrap_or_else(|| g.to_string())
-                    })
-                    .collect();
-
-                response.push_str(&format!(
-                    "Successfully kicked user {} from {} guild(s): {}\n",
-                    user_tag,
-                    kicked_from.len(),
-                    guild_names.join(", ")
-                ));
-            }
-            if !failed_guilds.is_empty() {
-                response.push_str(&format!(
-                    "Failed to kick from {} guild(s):\n",
-                    failed_guilds.len()
-                ));
-                for (guild_id, error) in &failed_guilds {
-                    let guild_name = ctx
-                        .cache
-                        .guild(*guild_id)
-                        .map(|g| format!("{} ({})", g.name, guild_id))
-                        .unwrap_or_else(|| guild_id.to_string());
-                    response.push_str(&format!("- Guild {}: {}\n", guild_name, error));
-                }
-            }
-            if kicked_from.is_empty() && failed_guilds.is_empty() {
-                response = format!("User {} was not found in any guilds.", user_tag);
-            }
+            let mut pacer = moderation::Pacer::new(&self.db).await;
+            let result = moderation::execute_kick(
+                ctx,
+                &self.db,
+                &mut pacer,
+                msg.author.id.get(),
+                user_id,
+                &user_tag,
+                &ctx.cache.guilds(),
+                reason.as_deref(),
+            )
+            .await;
 
-            self.send_response(ctx, msg, response, "/kick", !kicked_from.is_empty())
-                .await?;
+            self.send_response(
+                ctx,
+                msg,
+                result.summary(ctx, i18n::DEFAULT_LOCALE),
+                "/kick",
+                result.is_success(),
+            )
+            .await?;
         } else {
             self.send_response(
                 ctx,
                 msg,
-                format!(
-                    "User '{}' not found. Please use their username, @handle, or server nickname.",
-                    user_handle
-                ),
+                self.user_not_found_message(ctx, user_handle),
                 "/kick",
                 false,
             )
@@ -407,92 +519,33 @@ impl CommandHandler {
         };
 
         if let Some((user_id, user_tag)) = self.find_user_by_handle(ctx, user_handle).await {
-            let guilds = ctx.cache.guilds();
-            let mut banned_from = Vec::new();
-            let mut failed_guilds = Vec::new();
-
-            for guild_id in guilds {
-                let result = if let Some(reason) = reason.as_deref() {
-                    guild_id
-                        .ban_with_reason(&ctx.http, user_id, 0, reason)
-                        .await
-                } else {
-                    guild_id.ban(&ctx.http, user_id, 0).await
-                };
-
-                match result {
-                    Ok(_) => {
-                        // Get guild name from cache
-                        let guild_name = ctx
-                            .cache
-                            .guild(guild_id)
-                            .map(|g| g.name.clone())
-                            .unwrap_or_else(|| "Unknown".to_string());
-
-                        info!(
-                            "[MOD ACTION] {} banned user {} ({}) from guild {} ({}) - reason: {}",
-                            msg.author.id,
-                            user_tag,
-                            user_id,
-                            guild_name,
-                            guild_id,
-                            reason.as_deref().unwrap_or("none")
-                        );
-                        banned_from.push(guild_id);
-                    }
-                    Err(e) => {
-                        failed_guilds.push((guild_id, e.to_string()));
-                    }
-                }
-            }
-
-            let mut response = String::new();
-            if !banned_from.is_empty() {
-                let guild_names: Vec<String> = banned_from
-                    .iter()
-                    .map(|g| {
-                        ctx.cache
-                            .guild(*g)
-                            .map(|guild| format!("{} ({})", guild.name, g))
-                            .unwrap_or_else(|| g.to_string())
-                    })
-                    .collect();
-
-                response.push_str(&format!(
-                    "Successfully banned user {} from {} guild(s): {}\n",
-                    user_tag,
-                    banned_from.len(),
-                    guild_names.join(", ")
-                ));
-            }
-            if !failed_guilds.is_empty() {
-                response.push_str(&format!(
-                    "Failed to ban from {} guild(s):\n",
-                    failed_guilds.len()
-                ));
-                for (guild_id, error) in &failed_guilds {
-                    let guild_name = ctx
-                        .cache
-                        .guild(*guild_id)
-                        .map(|g| format!("{} ({})", g.name, guild_id))
-                        .unwrap_or_else(|| guild_id.to_string());
-                    response.push_str(&format!("- Guild {}: {}\n", guild_name, error));
-                }
-            }
-            if banned_from.is_empty() && failed_guilds.is_empty() {
-                response = "No guilds found to ban the user from.".to_string();
-            }
+            let mut pacer = moderation::Pacer::new(&self.db).await;
+            let result = moderation::execute_ban(
+                ctx,
+                &self.db,
+                &mut pacer,
+                msg.author.id.get(),
+                user_id,
+                &user_tag,
+                &ctx.cache.guilds(),
+                0,
+                reason.as_deref(),
+            )
+            .await;
 
-            self.send_response(ctx, msg, response, "/ban", !banned_from.is_empty())
-                .await?;
+            self.send_response(
+                ctx,
+                msg,
+                result.summary(ctx, i18n::DEFAULT_LOCALE),
+                "/ban",
+                result.is_success(),
+            )
+            .await?;
         } else {
             self.send_response(
                 ctx,
                 msg,
-                format!(
-                    "User '{}' not found. Please use their username, @handle, or server nickname.",
-                    user_handle
-                ),
+                self.user_not_found_message(ctx, user_handle),
                 "/ban",
                 false,
             )
@@ -538,15 +591,13 @@ impl CommandHandler {
         if let Some((user_id, user_tag)) = self.find_user_by_handle(ctx, user_handle).await {
             if let Some(duration_minutes) = duration_minutes {
                 // Discord's maximum timeout duration is 28 days
-                const MAX_TIMEOUT_MINUTES: u64 = 28 * 24 * 60;
-
-                if duration_minutes > MAX_TIMEOUT_MINUTES {
+                if duration_minutes > moderation::MAX_TIMEOUT_MINUTES {
                     self.send_response(
                         ctx,
                         msg,
                         format!(
                             "Timeout duration cannot exceed 28 days ({} minutes). You specified {} minutes.",
-                            MAX_TIMEOUT_MINUTES, duration_minutes
+                            moderation::MAX_TIMEOUT_MINUTES, duration_minutes
                         ),
                         "/timeout",
                         false,
@@ -567,93 +618,28 @@ impl CommandHandler {
                     return Ok(());
                 }
 
-                let timeout_until =
-                    chrono::Utc::now() + chrono::Duration::minutes(duration_minutes as i64);
-                let timeout_str = timeout_until.to_rfc3339();
-
-                let guilds = ctx.cache.guilds();
-                let mut timed_out_from = Vec::new();
-                let mut failed_guilds = Vec::new();
-
-                for guild_id in guilds {
-                    // Check if the user is in this guild
-                    let is_member = ctx
-                        .cache
-                        .guild(guild_id)
-                        .map(|guild| guild.members.contains_key(&user_id))
-                        .unwrap_or(false);
-
-                    if is_member {
-                        let edit_member =
-                            EditMember::new().disable_communication_until(timeout_str.clone());
-                        match guild_id.edit_member(&ctx.http, user_id, edit_member).await {
-                            Ok(_) => {
-                                // Get guild name from cache
-                                let guild_name = ctx
-                                    .cache
-                                    .guild(guild_id)
-                                    .map(|g| g.name.clone())
-                                    .unwrap_or_else(|| "Unknown".to_string());
-
-                                info!(
-                                "[MOD ACTION] {} timed out user {} ({}) in guild {} ({}) for {} minutes - reason: {}",
-                                msg.author.id,
-                                user_tag,
-                                user_id,
-                                guild_name,
-                                guild_id,
-                                duration_minutes,
-                                reason.as_deref().unwrap_or("none")
-                            );
-                                timed_out_from.push(guild_id);
-                            }
-                            Err(e) => {
-                                failed_guilds.push((guild_id, e.to_string()));
-                            }
-                        }
-                    }
-                }
-
-                let mut response = String::new();
-                if !timed_out_from.is_empty() {
-                    let guild_names: Vec<String> = timed_out_from
-                        .iter()
-                        .map(|g| {
-                            ctx.cache
-                                .guild(*g)
-                                .map(|guild| format!("{} ({})", guild.name, g))
-                                .unwrap_or_else(|| g.to_string())
-                        })
-                        .collect();
-
-                    response.push_str(&format!(
-                        "Successfully timed out user {} for {} minutes in {} guild(s): {}\n",
-                        user_tag,
-                        duration_minutes,
-                        timed_out_from.len(),
-                        guild_names.join(", ")
-                    ));
-                }
-                if !failed_guilds.is_empty() {
-                    response.push_str(&format!(
-                        "Failed to timeout in {} guild(s):\n",
-                        failed_guilds.len()
-                    ));
-                    for (guild_id, error) in &failed_guilds {
-                        let guild_name = ctx
-                            .cache
-                            .guild(*guild_id)
-                            .map(|g| format!("{} ({})", g.name, guild_id))
-                            .unwrap_or_else(|| guild_id.to_string());
-                        response.push_str(&format!("- Guild {}: {}\n", guild_name, error));
-                    }
-                }
-                if timed_out_from.is_empty() && failed_guilds.is_empty() {
-                    response = format!("User {} was not found in any guilds.", user_tag);
-                }
+                let mut pacer = moderation::Pacer::new(&self.db).await;
+                let result = moderation::execute_timeout(
+                    ctx,
+                    &self.db,
+                    &mut pacer,
+                    msg.author.id.get(),
+                    user_id,
+                    &user_tag,
+                    &ctx.cache.guilds(),
+                    duration_minutes,
+                    reason.as_deref(),
+                )
+                .await;
 
-                self.send_response(ctx, msg, response, "/timeout", !timed_out_from.is_empty())
-                    .await?;
+                self.send_response(
+                    ctx,
+                    msg,
+                    result.summary(ctx, i18n::DEFAULT_LOCALE),
+                    "/timeout",
+                    result.is_success(),
+                )
+                .await?;
             } else {
                 self.send_response(
                     ctx,
@@ -668,10 +654,7 @@ impl CommandHandler {
             self.send_response(
                 ctx,
                 msg,
-                format!(
-                    "User '{}' not found. Please use their username, @handle, or server nickname.",
-                    user_handle
-                ),
+                self.user_not_found_message(ctx, user_handle),
                 "/timeout",
                 false,
             )
@@ -696,22 +679,16 @@ impl CommandHandler {
 
         if args.is_empty() {
             // Show current status
-            let current_status = self
-                .db
-                .get_setting("cache_media")
-                .await?
-                .unwrap_or_else(|| "false".to_string());
+            let enabled = crate::settings::Settings::new(&self.db)
+                .get_bool("cache_media")
+                .await;
 
             self.send_response(
                 ctx,
                 msg,
                 format!(
                     "Media caching is currently: {}",
-                    if current_status == "true" {
-                        "ENABLED"
-                    } else {
-                        "DISABLED"
-                    }
+                    if enabled { "ENABLED" } else { "DISABLED" }
                 ),
                 "/cache",
                 true,
@@ -836,10 +813,7 @@ impl CommandHandler {
                     self.send_response(
                         ctx,
                         msg,
-                        format!(
-                            "User '{}' not found. Please use their username, @handle, or server nickname.",
-                            user_handle
-                        ),
+                        self.user_not_found_message(ctx, user_handle),
                         "/whitelist",
                         false,
                     )
@@ -895,10 +869,7 @@ impl CommandHandler {
                     self.send_response(
                         ctx,
                         msg,
-                        format!(
-                            "User '{}' not found. Please use their username, @handle, or server nickname.",
-                            user_handle
-                        ),
+                        self.user_not_found_message(ctx, user_handle),
                         "/whitelist",
                         false,
                     )
@@ -920,3 +891,39 @@ impl CommandHandler {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn suggests_kick_for_swapped_letters() {
+        assert_eq!(CommandHandler::suggest_command("/kcik"), Some("/kick"));
+    }
+
+    #[test]
+    fn suggests_commands_for_transpositions() {
+        assert_eq!(CommandHandler::suggest_command("/bna"), Some("/ban"));
+        assert_eq!(
+            CommandHandler::suggest_command("/tiemout"),
+            Some("/timeout")
+        );
+        assert_eq!(CommandHandler::suggest_command("/cahce"), Some("/cache"));
+        assert_eq!(
+            CommandHandler::suggest_command("/whitelsit"),
+            Some("/whitelist")
+        );
+    }
+
+    #[test]
+    fn suggests_aliases_edit_distance_misses() {
+        assert_eq!(CommandHandler::suggest_command("/mute"), Some("/timeout"));
+        assert_eq!(CommandHandler::suggest_command("/WL"), Some("/whitelist"));
+    }
+
+    #[test]
+    fn no_suggestion_for_distant_input() {
+        assert_eq!(CommandHandler::suggest_command("/zzzzzz"), None);
+        assert_eq!(CommandHandler::suggest_command("/qwerty"), None);
+    }
+}
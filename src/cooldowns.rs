@@ -0,0 +1,46 @@
+use anyhow::Result;
+
+use crate::db::Database;
+
+/// Outcome of [`Cooldowns::try_use`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CooldownResult {
+    Allowed,
+    OnCooldown { remaining_secs: u64 },
+}
+
+/// Per-user cooldowns shared by every rate-limited command, stored in
+/// `command_cooldowns` keyed by user and an arbitrary command key.
+pub struct Cooldowns<'a> {
+    db: &'a Database,
+}
+
+impl<'a> Cooldowns<'a> {
+    pub fn new(db: &'a Database) -> Self {
+        Self { db }
+    }
+
+    /// Uses `key` for `user_id` if at least `duration_secs` have passed since the last
+    /// allowed use, restarting the cooldown. A duration of 0 always allows the use.
+    pub async fn try_use(
+        &self,
+        user_id: u64,
+        key: &str,
+        duration_secs: u64,
+    ) -> Result<CooldownResult> {
+        if duration_secs == 0 {
+            return Ok(CooldownResult::Allowed);
+        }
+
+        let (allowed, remaining_secs) = self
+            .db
+            .check_and_update_cooldown(user_id, key, duration_secs)
+            .await?;
+
+        Ok(if allowed {
+            CooldownResult::Allowed
+        } else {
+            CooldownResult::OnCooldown { remaining_secs }
+        })
+    }
+}
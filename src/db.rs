@@ -1,22 +1,322 @@
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use sqlx::{MySql, Pool};
+use std::fmt;
+
+use crate::media_detector::normalize_title;
+
+/// Database failures that callers may want to report differently to users.
+#[derive(Debug)]
+pub enum DbError {
+    /// The row being acted on (or a row it references) doesn't exist.
+    NotFound,
+    /// A unique constraint rejected the write.
+    Duplicate,
+    /// The database couldn't be reached.
+    Connection(sqlx::Error),
+    Other(sqlx::Error),
+}
+
+pub type DbResult<T> = std::result::Result<T, DbError>;
+
+impl fmt::Display for DbError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DbError::NotFound => write!(f, "record not found"),
+            DbError::Duplicate => write!(f, "record already exists"),
+            DbError::Connection(e) => write!(f, "database connection error: {}", e),
+            DbError::Other(e) => write!(f, "database error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for DbError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DbError::Connection(e) | DbError::Other(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<sqlx::Error> for DbError {
+    fn from(e: sqlx::Error) -> Self {
+        match &e {
+            sqlx::Error::RowNotFound => DbError::NotFound,
+            sqlx::Error::Database(db_err) if db_err.is_unique_violation() => DbError::Duplicate,
+            sqlx::Error::Database(db_err) if db_err.is_foreign_key_violation() => DbError::NotFound,
+            _ if is_connection_error(&e) => DbError::Connection(e),
+            _ => DbError::Other(e),
+        }
+    }
+}
+
+/// What happened in a `voice_logs` row. Channel moves pair up into sessions for
+/// `/voicetime`; the rest record mute, deafen and streaming transitions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VoiceAction {
+    Join,
+    Leave,
+    Switch,
+    SelfMute,
+    SelfUnmute,
+    SelfDeaf,
+    SelfUndeaf,
+    ServerMute,
+    ServerUnmute,
+    ServerDeaf,
+    ServerUndeaf,
+    StreamStart,
+    StreamStop,
+}
+
+impl VoiceAction {
+    /// The value stored in `voice_logs.action`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            VoiceAction::Join => "join",
+            VoiceAction::Leave => "leave",
+            VoiceAction::Switch => "switch",
+            VoiceAction::SelfMute => "self_mute",
+            VoiceAction::SelfUnmute => "self_unmute",
+            VoiceAction::SelfDeaf => "self_deaf",
+            VoiceAction::SelfUndeaf => "self_undeaf",
+            VoiceAction::ServerMute => "server_mute",
+            VoiceAction::ServerUnmute => "server_unmute",
+            VoiceAction::ServerDeaf => "server_deaf",
+            VoiceAction::ServerUndeaf => "server_undeaf",
+            VoiceAction::StreamStart => "stream_start",
+            VoiceAction::StreamStop => "stream_stop",
+        }
+    }
+}
+
+impl fmt::Display for VoiceAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let description = match self {
+            VoiceAction::Join => "joined",
+            VoiceAction::Leave => "left",
+            VoiceAction::Switch => "switched to",
+            VoiceAction::SelfMute => "muted themselves in",
+            VoiceAction::SelfUnmute => "unmuted themselves in",
+            VoiceAction::SelfDeaf => "deafened themselves in",
+            VoiceAction::SelfUndeaf => "undeafened themselves in",
+            VoiceAction::ServerMute => "was server muted in",
+            VoiceAction::ServerUnmute => "was server unmuted in",
+            VoiceAction::ServerDeaf => "was server deafened in",
+            VoiceAction::ServerUndeaf => "was server undeafened in",
+            VoiceAction::StreamStart => "started streaming in",
+            VoiceAction::StreamStop => "stopped streaming in",
+        };
+        f.write_str(description)
+    }
+}
+
+/// Whether an error came from reaching the database rather than from the query itself.
+fn is_connection_error(e: &sqlx::Error) -> bool {
+    matches!(
+        e,
+        sqlx::Error::Io(_)
+            | sqlx::Error::Tls(_)
+            | sqlx::Error::PoolTimedOut
+            | sqlx::Error::PoolClosed
+            | sqlx::Error::WorkerCrashed
+    )
+}
+
+/// Whether an error means the query never reached the server, so running it again can't
+/// insert a row twice. A connection dropped mid-query may already have committed.
+fn failed_before_execution(e: &sqlx::Error) -> bool {
+    match e {
+        sqlx::Error::PoolTimedOut => true,
+        sqlx::Error::Io(io) => io.kind() == std::io::ErrorKind::ConnectionRefused,
+        _ => false,
+    }
+}
+
+const DB_RETRY_ATTEMPTS: u32 = 3;
+const DB_RETRY_BASE_DELAY_MS: u64 = 100;
+
+/// Runs a query, retrying with exponential backoff when it failed before reaching the
+/// database (pool timeout, connection refused). Anything else, including connections lost
+/// mid-query, is returned immediately so non-idempotent INSERTs aren't repeated.
+async fn query_with_retry<T, F, Fut>(operation: &str, mut query: F) -> Result<T, sqlx::Error>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<T, sqlx::Error>>,
+{
+    let mut attempt = 0;
+    loop {
+        match query().await {
+            Ok(value) => return Ok(value),
+            Err(e) if attempt + 1 < DB_RETRY_ATTEMPTS && failed_before_execution(&e) => {
+                let delay_ms = DB_RETRY_BASE_DELAY_MS * 2u64.pow(attempt);
+                tracing::warn!(
+                    "{} failed ({}), retrying in {}ms (attempt {}/{})",
+                    operation,
+                    e,
+                    delay_ms,
+                    attempt + 1,
+                    DB_RETRY_ATTEMPTS
+                );
+                tokio::time::sleep(tokio::time::Duration::from_millis(delay_ms)).await;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// A global watchlist entry: id, media type, title, URL, description, upvotes,
+/// downvotes, adder's username, adder's user ID and when it was added.
+pub type GlobalWatchlistItem = (
+    i32,
+    String,
+    String,
+    Option<String>,
+    Option<String>,
+    i64,
+    i64,
+    String,
+    i64,
+    DateTime<Utc>,
+    Option<DateTime<Utc>>,
+);
+
+/// A watchlist item as exported and imported: media type, title, URL, priority, status
+/// and notes.
+pub type WatchlistExportItem = (String, String, Option<String>, i32, String, Option<String>);
+
+/// A recommendation export row: media type, title, URL, average confidence, mention count
+/// and up to 10 of the users who mentioned it.
+pub type RecommendationExportItem = (String, String, Option<String>, f32, i64, Vec<String>);
+
+/// A user row for `Database::update_users_batch`.
+pub struct UserRecord {
+    pub user_id: u64,
+    pub username: String,
+    pub discriminator: Option<String>,
+    pub global_handle: Option<String>,
+    pub nickname: Option<String>,
+}
+
+impl UserRecord {
+    pub fn from_member(member: &serenity::all::Member) -> Self {
+        let user = &member.user;
+        Self {
+            user_id: user.id.get(),
+            username: user.name.clone(),
+            discriminator: user.discriminator.map(|d| d.get().to_string()),
+            // Migrated accounts have no discriminator and use their name as the handle
+            global_handle: user.discriminator.is_none().then(|| user.name.clone()),
+            nickname: member.nick.clone(),
+        }
+    }
+}
+
+/// Rows per multi-row user upsert. Each row binds 5 values, keeping a statement well
+/// under MySQL's 65,535 placeholder limit.
+const USER_BATCH_SIZE: usize = 1000;
+
+/// Connection pool sizing, set from `DB_MAX_CONNECTIONS`, `DB_MIN_CONNECTIONS` and
+/// `DB_ACQUIRE_TIMEOUT_SECS`.
+#[derive(Debug, Clone, Copy)]
+pub struct PoolSettings {
+    pub max_connections: u32,
+    pub min_connections: u32,
+    /// How long a query waits for a free connection before failing.
+    pub acquire_timeout: std::time::Duration,
+}
+
+impl Default for PoolSettings {
+    fn default() -> Self {
+        Self {
+            max_connections: 5,
+            min_connections: 0,
+            acquire_timeout: std::time::Duration::from_secs(30),
+        }
+    }
+}
 
 #[derive(Clone)]
 pub struct Database {
     pub pool: Pool<MySql>,
 }
 
+/// Inserts a watchlist row or updates the existing one. `status` is only
+/// changed when given; new rows default to `plan_to_watch`.
+#[allow(clippy::too_many_arguments)]
+async fn upsert_watchlist_item<'e, E>(
+    executor: E,
+    user_id: u64,
+    media_type: &str,
+    title: &str,
+    url: Option<&str>,
+    priority: Option<i32>,
+    status: Option<&str>,
+    notes: Option<&str>,
+) -> Result<()>
+where
+    E: sqlx::Executor<'e, Database = MySql>,
+{
+    sqlx::query(
+        r#"
+        INSERT INTO user_watchlist (user_id, media_type, title, url, priority, status, notes)
+        VALUES (?, ?, ?, ?, ?, COALESCE(?, 'plan_to_watch'), ?)
+        ON DUPLICATE KEY UPDATE 
+            url = COALESCE(VALUES(url), url),
+            priority = COALESCE(VALUES(priority), priority),
+            status = IF(? IS NULL, status, VALUES(status)),
+            notes = COALESCE(VALUES(notes), notes),
+            updated_at = NOW()
+        "#,
+    )
+    .bind(user_id as i64)
+    .bind(media_type)
+    .bind(title)
+    .bind(url)
+    .bind(priority.unwrap_or(50))
+    .bind(status)
+    .bind(notes)
+    .bind(status)
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
 impl Database {
-    pub async fn new(database_url: &str) -> Result<Self> {
+    pub async fn new(database_url: &str, pool_settings: PoolSettings) -> Result<Self> {
         let pool = sqlx::mysql::MySqlPoolOptions::new()
-            .max_connections(5)
+            .max_connections(pool_settings.max_connections)
+            .min_connections(pool_settings.min_connections)
+            .acquire_timeout(pool_settings.acquire_timeout)
             .connect(database_url)
             .await?;
 
         Ok(Self { pool })
     }
 
+    /// Open connections, how many of those are idle, and the configured maximum.
+    pub fn pool_stats(&self) -> (u32, usize, u32) {
+        (
+            self.pool.size(),
+            self.pool.num_idle(),
+            self.pool.options().get_max_connections(),
+        )
+    }
+
+    pub async fn ping(&self) -> Result<()> {
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
+
+        Ok(())
+    }
+
+    /// Closes the pool, waiting for queries already in flight to finish.
+    pub async fn close(&self) {
+        self.pool.close().await;
+    }
+
     pub async fn run_migrations(&self) -> Result<()> {
         // Run sqlx migrations from the migrations directory
         sqlx::migrate!("./migrations").run(&self.pool).await?;
@@ -29,58 +329,393 @@ impl Database {
         message_id: u64,
         user_id: u64,
         channel_id: u64,
+        guild_id: Option<u64>,
         content: &str,
         timestamp: DateTime<Utc>,
     ) -> Result<()> {
+        query_with_retry("log_message", || {
+            sqlx::query(
+                "INSERT INTO message_logs (message_id, user_id, channel_id, guild_id, content, timestamp) VALUES (?, ?, ?, ?, ?, ?)"
+            )
+            .bind(message_id as i64)
+            .bind(user_id as i64)
+            .bind(channel_id as i64)
+            .bind(guild_id.map(|id| id as i64))
+            .bind(content)
+            .bind(timestamp)
+            .execute(&self.pool)
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    /// Records an edit in `message_edit_logs` and updates `message_logs` to the new content.
+    ///
+    /// The prior content is read from `message_logs` first so every edit keeps its before and
+    /// after text. `new_content` is `None` when only attachments or embeds changed, in which
+    /// case the stored content is left alone. `attachments` is a snapshot of the attachment
+    /// URLs after the edit.
+    pub async fn log_message_edit(
+        &self,
+        message_id: u64,
+        new_content: Option<&str>,
+        attachments: Option<&[String]>,
+        embed_count: Option<usize>,
+        edited_at: DateTime<Utc>,
+    ) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        let old_content: Option<Option<String>> =
+            sqlx::query_scalar("SELECT content FROM message_logs WHERE message_id = ? FOR UPDATE")
+                .bind(message_id as i64)
+                .fetch_optional(&mut *tx)
+                .await?;
+        let old_content = old_content.flatten();
+
+        let attachments_json = attachments.map(serde_json::to_string).transpose()?;
+
         sqlx::query(
-            "INSERT INTO message_logs (message_id, user_id, channel_id, content, timestamp) VALUES (?, ?, ?, ?, ?)"
+            r#"
+            INSERT INTO message_edit_logs
+                (message_id, old_content, new_content, attachments, embed_count, edited_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
         )
         .bind(message_id as i64)
-        .bind(user_id as i64)
-        .bind(channel_id as i64)
-        .bind(content)
-        .bind(timestamp)
-        .execute(&self.pool)
+        .bind(&old_content)
+        .bind(new_content.or(old_content.as_deref()))
+        .bind(attachments_json)
+        .bind(embed_count.map(|count| count as i32))
+        .bind(edited_at)
+        .execute(&mut *tx)
         .await?;
 
+        match new_content {
+            Some(content) => {
+                sqlx::query(
+                    "UPDATE message_logs SET content = ?, edited = TRUE WHERE message_id = ?",
+                )
+                .bind(content)
+                .bind(message_id as i64)
+                .execute(&mut *tx)
+                .await?;
+            }
+            None => {
+                sqlx::query("UPDATE message_logs SET edited = TRUE WHERE message_id = ?")
+                    .bind(message_id as i64)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+        }
+
+        tx.commit().await?;
+
         Ok(())
     }
 
-    pub async fn log_message_edit(&self, message_id: u64, new_content: &str) -> Result<()> {
-        sqlx::query("UPDATE message_logs SET content = ?, edited = TRUE WHERE message_id = ?")
-            .bind(new_content)
-            .bind(message_id as i64)
+    pub async fn mark_messages_deleted(
+        &self,
+        message_ids: &[u64],
+        deleted_by: Option<u64>,
+    ) -> Result<u64> {
+        if message_ids.is_empty() {
+            return Ok(0);
+        }
+
+        let placeholders = vec!["?"; message_ids.len()].join(", ");
+        let sql = format!(
+            "UPDATE message_logs SET deleted = TRUE, deleted_at = NOW(), deleted_by = ? WHERE message_id IN ({})",
+            placeholders
+        );
+
+        let mut query = sqlx::query(&sql).bind(deleted_by.map(|id| id as i64));
+        for message_id in message_ids {
+            query = query.bind(*message_id as i64);
+        }
+
+        let result = query.execute(&self.pool).await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn log_voice_event(
+        &self,
+        user_id: u64,
+        channel_id: u64,
+        guild_id: u64,
+        action: VoiceAction,
+    ) -> Result<()> {
+        query_with_retry("log_voice_event", || {
+            sqlx::query(
+                "INSERT INTO voice_logs (user_id, channel_id, guild_id, action, timestamp) VALUES (?, ?, ?, ?, NOW())"
+            )
+            .bind(user_id as i64)
+            .bind(channel_id as i64)
+            .bind(guild_id as i64)
+            .bind(action.as_str())
             .execute(&self.pool)
-            .await?;
+        })
+        .await?;
 
         Ok(())
     }
 
-    pub async fn log_voice_event(&self, user_id: u64, channel_id: u64, action: &str) -> Result<()> {
-        sqlx::query(
-            "INSERT INTO voice_logs (user_id, channel_id, action, timestamp) VALUES (?, ?, ?, NOW())"
-        )
-        .bind(user_id as i64)
-        .bind(channel_id as i64)
-        .bind(action)
-        .execute(&self.pool)
+    pub async fn log_membership_event(
+        &self,
+        user_id: u64,
+        guild_id: u64,
+        action: &str,
+    ) -> Result<()> {
+        query_with_retry("log_membership_event", || {
+            sqlx::query(
+                "INSERT INTO member_logs (user_id, guild_id, action, timestamp) VALUES (?, ?, ?, NOW())",
+            )
+            .bind(user_id as i64)
+            .bind(guild_id as i64)
+            .bind(action)
+            .execute(&self.pool)
+        })
         .await?;
 
         Ok(())
     }
 
+    /// Assigns `guild_id` to logged messages from the given channels that were stored without
+    /// one. Returns how many rows were updated.
+    pub async fn backfill_message_guild(&self, guild_id: u64, channel_ids: &[u64]) -> Result<u64> {
+        if channel_ids.is_empty() {
+            return Ok(0);
+        }
+
+        let placeholders = vec!["?"; channel_ids.len()].join(", ");
+        let sql = format!(
+            "UPDATE message_logs SET guild_id = ? WHERE guild_id IS NULL AND channel_id IN ({})",
+            placeholders
+        );
+
+        let mut query = sqlx::query(&sql).bind(guild_id as i64);
+        for channel_id in channel_ids {
+            query = query.bind(*channel_id as i64);
+        }
+
+        let result = query.execute(&self.pool).await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Messages still held in `message_logs` and media recommendations detected for a guild.
+    pub async fn get_guild_log_stats(&self, guild_id: u64) -> Result<(i64, i64)> {
+        let messages =
+            sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM message_logs WHERE guild_id = ?")
+                .bind(guild_id as i64)
+                .fetch_one(&self.pool)
+                .await?;
+
+        let recommendations = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM media_recommendations WHERE guild_id = ?",
+        )
+        .bind(guild_id as i64)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok((messages, recommendations))
+    }
+
+    /// Join and leave counts for a guild over the last `days` days, plus how many of the
+    /// members who joined in that window have since left.
+    pub async fn get_membership_churn(&self, guild_id: u64, days: i32) -> Result<(i64, i64, i64)> {
+        let cutoff = chrono::Utc::now() - chrono::Duration::days(days as i64);
+
+        let counts = sqlx::query_as(
+            r#"
+            SELECT
+                CAST(COALESCE(SUM(j.action = 'join'), 0) AS SIGNED) as joins,
+                CAST(COALESCE(SUM(j.action = 'leave'), 0) AS SIGNED) as leaves,
+                CAST(COUNT(DISTINCT CASE
+                    WHEN j.action = 'join' AND EXISTS (
+                        SELECT 1 FROM member_logs l
+                        WHERE l.guild_id = j.guild_id
+                          AND l.user_id = j.user_id
+                          AND l.action = 'leave'
+                          AND l.timestamp > j.timestamp
+                    ) THEN j.user_id
+                END) AS SIGNED) as joined_then_left
+            FROM member_logs j
+            WHERE j.guild_id = ? AND j.timestamp > ?
+            "#,
+        )
+        .bind(guild_id as i64)
+        .bind(cutoff)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(counts)
+    }
+
+    /// Sums time spent in voice per user for a guild over the last `days` days.
+    ///
+    /// Sessions are rebuilt by pairing join/leave events; `switch` keeps the session open,
+    /// a leave without a join counts from the start of the window, and a session that is
+    /// still open is capped at now.
+    pub async fn get_voice_time_leaderboard(
+        &self,
+        guild_id: u64,
+        days: i32,
+        limit: usize,
+    ) -> Result<Vec<(u64, i64)>> {
+        let window_start = Utc::now() - chrono::Duration::days(days as i64);
+
+        let events: Vec<(i64, String, DateTime<Utc>)> = sqlx::query_as(
+            r#"
+            SELECT user_id, action, timestamp
+            FROM voice_logs
+            WHERE guild_id = ? AND timestamp >= ?
+            ORDER BY user_id, timestamp ASC, id ASC
+            "#,
+        )
+        .bind(guild_id as i64)
+        .bind(window_start)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let now = Utc::now();
+        let mut totals: std::collections::HashMap<u64, i64> = std::collections::HashMap::new();
+        let mut open_sessions: std::collections::HashMap<u64, DateTime<Utc>> =
+            std::collections::HashMap::new();
+
+        for (user_id, action, timestamp) in events {
+            let user_id = user_id as u64;
+            match action.as_str() {
+                // A repeated join means we missed a leave, so restart the session
+                "join" => {
+                    open_sessions.insert(user_id, timestamp);
+                }
+                // Switching channels doesn't end the session, but proves the user is in voice
+                "switch" => {
+                    open_sessions.entry(user_id).or_insert(timestamp);
+                }
+                "leave" => {
+                    let start = open_sessions.remove(&user_id).unwrap_or(window_start);
+                    *totals.entry(user_id).or_insert(0) += (timestamp - start).num_seconds();
+                }
+                _ => {}
+            }
+        }
+
+        for (user_id, start) in open_sessions {
+            *totals.entry(user_id).or_insert(0) += (now - start).num_seconds();
+        }
+
+        let mut leaderboard: Vec<(u64, i64)> = totals
+            .into_iter()
+            .filter(|(_, seconds)| *seconds > 0)
+            .collect();
+        leaderboard.sort_by_key(|(_, seconds)| std::cmp::Reverse(*seconds));
+        leaderboard.truncate(limit);
+
+        Ok(leaderboard)
+    }
+
+    /// Peak concurrent users and unique participants per voice channel in a guild over the
+    /// last `days` days, as (channel_id, peak, unique), busiest first.
+    ///
+    /// Occupancy is replayed from join/leave/switch events. A `switch` row only names the
+    /// channel joined, so the channel left is taken from the user's previous event, and a
+    /// user whose first event in the window is a leave is counted as present from the start.
+    pub async fn get_voice_channel_activity(
+        &self,
+        guild_id: u64,
+        days: i32,
+    ) -> Result<Vec<(u64, usize, usize)>> {
+        use std::collections::{HashMap, HashSet};
+
+        let window_start = Utc::now() - chrono::Duration::days(days as i64);
+
+        let events: Vec<(i64, i64, String)> = sqlx::query_as(
+            r#"
+            SELECT user_id, channel_id, action
+            FROM voice_logs
+            WHERE guild_id = ? AND timestamp >= ? AND action IN ('join', 'leave', 'switch')
+            ORDER BY timestamp ASC, id ASC
+            "#,
+        )
+        .bind(guild_id as i64)
+        .bind(window_start)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut location: HashMap<u64, u64> = HashMap::new();
+        let mut occupancy: HashMap<u64, usize> = HashMap::new();
+        let mut peaks: HashMap<u64, usize> = HashMap::new();
+        let mut participants: HashMap<u64, HashSet<u64>> = HashMap::new();
+
+        // Users already connected when the window opened show up first as a leave
+        let mut seen = HashSet::new();
+        let already_connected: Vec<(u64, u64)> = events
+            .iter()
+            .filter(|(user_id, _, _)| seen.insert(*user_id))
+            .filter(|(_, _, action)| action == "leave")
+            .map(|(user_id, channel_id, _)| (*user_id as u64, *channel_id as u64))
+            .collect();
+
+        let moves = already_connected
+            .into_iter()
+            .map(|(user_id, channel_id)| (user_id, channel_id, true))
+            .chain(events.into_iter().map(|(user_id, channel_id, action)| {
+                (user_id as u64, channel_id as u64, action != "leave")
+            }));
+
+        for (user_id, channel_id, entering) in moves {
+            // Any earlier channel is left first, which also covers a missed leave before a join
+            if let Some(previous) = location.remove(&user_id) {
+                if let Some(count) = occupancy.get_mut(&previous) {
+                    *count = count.saturating_sub(1);
+                }
+            }
+
+            if entering {
+                location.insert(user_id, channel_id);
+                let count = occupancy.entry(channel_id).or_insert(0);
+                *count += 1;
+                let peak = peaks.entry(channel_id).or_insert(0);
+                *peak = (*peak).max(*count);
+                participants.entry(channel_id).or_default().insert(user_id);
+            }
+        }
+
+        let mut activity: Vec<(u64, usize, usize)> = peaks
+            .into_iter()
+            .map(|(channel_id, peak)| {
+                let unique = participants.get(&channel_id).map_or(0, |users| users.len());
+                (channel_id, peak, unique)
+            })
+            .collect();
+        activity.sort_by_key(|(channel_id, peak, unique)| {
+            (
+                std::cmp::Reverse(*peak),
+                std::cmp::Reverse(*unique),
+                *channel_id,
+            )
+        });
+
+        Ok(activity)
+    }
+
     pub async fn log_forum_thread(
         &self,
         thread_id: u64,
         user_id: u64,
+        guild_id: u64,
+        parent_id: Option<u64>,
         title: &str,
         content: &str,
     ) -> Result<()> {
         sqlx::query(
-            "INSERT INTO forum_logs (thread_id, user_id, title, content, created_at) VALUES (?, ?, ?, ?, NOW())"
+            "INSERT INTO forum_logs (thread_id, user_id, guild_id, parent_id, title, content, created_at) VALUES (?, ?, ?, ?, ?, ?, NOW())"
         )
         .bind(thread_id as i64)
         .bind(user_id as i64)
+        .bind(guild_id as i64)
+        .bind(parent_id.map(|id| id as i64))
         .bind(title)
         .bind(content)
         .execute(&self.pool)
@@ -89,6 +724,67 @@ impl Database {
         Ok(())
     }
 
+    /// Threads started in a guild over the last `days` days: the top `limit` starters as
+    /// (user_id, username, threads) and the top `limit` parent channels as (channel_id, threads).
+    pub async fn get_thread_stats(
+        &self,
+        guild_id: u64,
+        days: i32,
+        limit: u32,
+    ) -> Result<(Vec<(u64, String, i64)>, Vec<(u64, i64)>)> {
+        let cutoff = chrono::Utc::now() - chrono::Duration::days(days as i64);
+
+        let starters: Vec<(i64, Option<String>, i64)> = sqlx::query_as(
+            r#"
+            SELECT f.user_id, COALESCE(u.global_handle, u.username) as display_name, COUNT(*) as threads
+            FROM forum_logs f
+            LEFT JOIN users u ON u.discord_user_id = f.user_id
+            WHERE f.guild_id = ? AND f.created_at > ?
+            GROUP BY f.user_id, display_name
+            ORDER BY threads DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(guild_id as i64)
+        .bind(cutoff)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let channels: Vec<(i64, i64)> = sqlx::query_as(
+            r#"
+            SELECT parent_id, COUNT(*) as threads
+            FROM forum_logs
+            WHERE guild_id = ? AND created_at > ? AND parent_id IS NOT NULL
+            GROUP BY parent_id
+            ORDER BY threads DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(guild_id as i64)
+        .bind(cutoff)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok((
+            starters
+                .into_iter()
+                .map(|(user_id, name, threads)| {
+                    (
+                        user_id as u64,
+                        name.unwrap_or_else(|| "Unknown".to_string()),
+                        threads,
+                    )
+                })
+                .collect(),
+            channels
+                .into_iter()
+                .map(|(channel_id, threads)| (channel_id as u64, threads))
+                .collect(),
+        ))
+    }
+
     pub async fn update_user(
         &self,
         user_id: u64,
@@ -120,33 +816,171 @@ impl Database {
         Ok(())
     }
 
-    pub async fn is_whitelisted(&self, user_id: u64) -> Result<bool> {
-        // Check if user is a super user first
-        if self.is_super_user(user_id).await? {
-            return Ok(true);
-        }
+    /// Upserts many users with one multi-row statement per `USER_BATCH_SIZE` rows,
+    /// instead of a round-trip per user.
+    pub async fn update_users_batch(&self, users: Vec<UserRecord>) -> Result<()> {
+        for chunk in users.chunks(USER_BATCH_SIZE) {
+            let mut builder = sqlx::QueryBuilder::<MySql>::new(
+                "INSERT INTO users (discord_user_id, username, discriminator, global_handle, nickname, last_seen) ",
+            );
+            builder.push_values(chunk, |mut row, user| {
+                row.push_bind(user.user_id as i64)
+                    .push_bind(&user.username)
+                    .push_bind(&user.discriminator)
+                    .push_bind(&user.global_handle)
+                    .push_bind(&user.nickname)
+                    .push("NOW()");
+            });
+            builder.push(
+                r#"
+                ON DUPLICATE KEY UPDATE
+                    username = VALUES(username),
+                    discriminator = VALUES(discriminator),
+                    global_handle = VALUES(global_handle),
+                    nickname = VALUES(nickname),
+                    last_seen = NOW()
+                "#,
+            );
 
-        // Check regular whitelist
-        let result = sqlx::query_scalar::<_, i64>(
-            "SELECT COUNT(*) FROM command_whitelist WHERE discord_user_id = ?",
-        )
-        .bind(user_id as i64)
-        .fetch_one(&self.pool)
-        .await?;
+            builder.build().execute(&self.pool).await?;
+        }
 
-        Ok(result > 0)
+        Ok(())
     }
 
-    pub async fn is_super_user(&self, user_id: u64) -> Result<bool> {
-        let result = sqlx::query_scalar::<_, i64>(
-            "SELECT COUNT(*) FROM super_user_whitelist WHERE discord_user_id = ?",
-        )
-        .bind(user_id as i64)
-        .fetch_one(&self.pool)
-        .await?;
-
-        Ok(result > 0)
-    }
+    /// Returns the tracked profile for a user: username, global handle, nickname, last seen,
+    /// and the most recent presence status, activity and when it was recorded.
+    pub async fn get_user_profile(
+        &self,
+        user_id: u64,
+    ) -> Result<
+        Option<(
+            String,
+            Option<String>,
+            Option<String>,
+            Option<DateTime<Utc>>,
+            Option<String>,
+            Option<String>,
+            Option<DateTime<Utc>>,
+        )>,
+    > {
+        // username, global handle, nickname, last seen
+        type UserRow = (
+            Option<String>,
+            Option<String>,
+            Option<String>,
+            Option<DateTime<Utc>>,
+        );
+        // status, activity type, activity name, recorded at
+        type StatusRow = (
+            Option<String>,
+            Option<String>,
+            Option<String>,
+            DateTime<Utc>,
+        );
+
+        let user: Option<UserRow> =
+            sqlx::query_as(
+                "SELECT username, global_handle, nickname, last_seen FROM users WHERE discord_user_id = ?",
+            )
+            .bind(user_id as i64)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        let Some((username, global_handle, nickname, last_seen)) = user else {
+            return Ok(None);
+        };
+
+        let status: Option<StatusRow> = sqlx::query_as(
+            r#"
+                SELECT status, activity_type, activity_name, timestamp
+                FROM member_status_logs
+                WHERE user_id = ?
+                ORDER BY timestamp DESC
+                LIMIT 1
+                "#,
+        )
+        .bind(user_id as i64)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let (status, activity, status_time) = match status {
+            Some((status, activity_type, activity_name, timestamp)) => {
+                let activity = match (activity_type, activity_name) {
+                    (Some(kind), Some(name)) if kind != "None" && !name.is_empty() => {
+                        Some(format!("{} {}", kind, name))
+                    }
+                    _ => None,
+                };
+                (status, activity, Some(timestamp))
+            }
+            None => (None, None, None),
+        };
+
+        Ok(Some((
+            username.unwrap_or_default(),
+            global_handle,
+            nickname,
+            last_seen,
+            status,
+            activity,
+            status_time,
+        )))
+    }
+
+    pub async fn get_nickname_history(
+        &self,
+        user_id: u64,
+        limit: u32,
+    ) -> Result<Vec<(u64, Option<String>, Option<String>, DateTime<Utc>)>> {
+        type NicknameRow = (i64, Option<String>, Option<String>, DateTime<Utc>);
+        let rows: Vec<NicknameRow> = sqlx::query_as(
+            r#"
+            SELECT guild_id, old_nickname, new_nickname, timestamp
+            FROM nickname_logs
+            WHERE user_id = ?
+            ORDER BY timestamp DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(user_id as i64)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(guild_id, old, new, timestamp)| (guild_id as u64, old, new, timestamp))
+            .collect())
+    }
+
+    pub async fn is_whitelisted(&self, user_id: u64) -> Result<bool> {
+        // Check if user is a super user first
+        if self.is_super_user(user_id).await? {
+            return Ok(true);
+        }
+
+        // Check regular whitelist
+        let result = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM command_whitelist WHERE discord_user_id = ?",
+        )
+        .bind(user_id as i64)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(result > 0)
+    }
+
+    pub async fn is_super_user(&self, user_id: u64) -> Result<bool> {
+        let result = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM super_user_whitelist WHERE discord_user_id = ?",
+        )
+        .bind(user_id as i64)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(result > 0)
+    }
 
     pub async fn search_users(
         &self,
@@ -213,6 +1047,39 @@ impl Database {
         Ok(())
     }
 
+    pub async fn get_whitelisted_users(&self) -> Result<Vec<(u64, Option<String>, bool)>> {
+        let rows: Vec<(i64, Option<String>, i64)> = sqlx::query_as(
+            r#"
+            SELECT
+                w.discord_user_id,
+                u.username,
+                EXISTS(SELECT 1 FROM super_user_whitelist s WHERE s.discord_user_id = w.discord_user_id) as is_super
+            FROM (
+                SELECT discord_user_id FROM command_whitelist
+                UNION
+                SELECT discord_user_id FROM super_user_whitelist
+            ) w
+            LEFT JOIN users u ON u.discord_user_id = w.discord_user_id
+            ORDER BY is_super DESC, u.username ASC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(user_id, username, is_super)| (user_id as u64, username, is_super != 0))
+            .collect())
+    }
+
+    pub async fn count_super_users(&self) -> Result<i64> {
+        let count = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM super_user_whitelist")
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(count)
+    }
+
     pub async fn add_to_super_whitelist(&self, user_id: u64) -> Result<()> {
         sqlx::query("INSERT IGNORE INTO super_user_whitelist (discord_user_id) VALUES (?)")
             .bind(user_id as i64)
@@ -231,6 +1098,7 @@ impl Database {
         Ok(())
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn log_attachment(
         &self,
         message_id: u64,
@@ -304,6 +1172,54 @@ impl Database {
         Ok(())
     }
 
+    /// Deletes settings whose key starts with `prefix` and that haven't been touched in
+    /// `older_than_days` days. Returns how many were removed.
+    pub async fn delete_stale_settings(&self, prefix: &str, older_than_days: i64) -> Result<u64> {
+        // `_` is a LIKE wildcard and every prefix we clean up contains it
+        let pattern = format!("{}%", prefix.replace('\\', "\\\\").replace('_', "\\_"));
+
+        let result = sqlx::query(
+            r#"
+            DELETE FROM system_settings
+            WHERE setting_key LIKE ? AND updated_at < NOW() - INTERVAL ? DAY
+            "#,
+        )
+        .bind(pattern)
+        .bind(older_than_days)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Claims a setting as a flag, returning false if someone else already holds it.
+    /// Flags older than `stale_after_secs` are assumed to be left over from a crash.
+    pub async fn try_acquire_setting_flag(&self, key: &str, stale_after_secs: u64) -> Result<bool> {
+        sqlx::query(
+            r#"
+            DELETE FROM system_settings
+            WHERE setting_key = ? AND updated_at < NOW() - INTERVAL ? SECOND
+            "#,
+        )
+        .bind(key)
+        .bind(stale_after_secs)
+        .execute(&self.pool)
+        .await?;
+
+        let result = sqlx::query(
+            r#"
+            INSERT IGNORE INTO system_settings (setting_key, setting_value)
+            VALUES (?, 'true')
+            "#,
+        )
+        .bind(key)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    #[allow(dead_code)]
     pub async fn get_all_settings(&self) -> Result<Vec<(String, String)>> {
         let settings: Vec<(String, String)> =
             sqlx::query_as("SELECT setting_key, setting_value FROM system_settings")
@@ -326,6 +1242,74 @@ impl Database {
         Ok(paths)
     }
 
+    /// How many attachments point at a cached file. Deduplicated uploads share one file,
+    /// so it may only be deleted once this reaches zero.
+    pub async fn count_attachments_with_local_path(&self, local_path: &str) -> Result<i64> {
+        let count = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM message_attachments WHERE local_path = ?",
+        )
+        .bind(local_path)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    pub async fn get_cached_media_oldest_first(&self) -> Result<Vec<(u64, String)>> {
+        let rows: Vec<(i64, String)> = sqlx::query_as(
+            "SELECT attachment_id, local_path FROM message_attachments WHERE local_path IS NOT NULL ORDER BY cached_at ASC"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(attachment_id, path)| (attachment_id as u64, path))
+            .collect())
+    }
+
+    pub async fn get_media_hash_paths(&self, content_hash: &str) -> Result<Vec<String>> {
+        let paths = sqlx::query_scalar::<_, String>(
+            "SELECT file_path FROM media_hashes WHERE content_hash = ? ORDER BY id ASC",
+        )
+        .bind(content_hash)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(paths)
+    }
+
+    pub async fn record_media_hash(
+        &self,
+        content_hash: &str,
+        file_path: &str,
+        size: u64,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO media_hashes (content_hash, file_path, size)
+            VALUES (?, ?, ?)
+            ON DUPLICATE KEY UPDATE content_hash = VALUES(content_hash), size = VALUES(size)
+            "#,
+        )
+        .bind(content_hash)
+        .bind(file_path)
+        .bind(size as i64)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn remove_media_hash_path(&self, file_path: &str) -> Result<()> {
+        sqlx::query("DELETE FROM media_hashes WHERE file_path = ?")
+            .bind(file_path)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
     pub async fn clear_local_path(&self, attachment_id: u64) -> Result<()> {
         sqlx::query("UPDATE message_attachments SET local_path = NULL WHERE attachment_id = ?")
             .bind(attachment_id as i64)
@@ -393,6 +1377,7 @@ impl Database {
         Ok(())
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn log_channel_change(
         &self,
         channel_id: u64,
@@ -480,15 +1465,6 @@ impl Database {
             .execute(&self.pool)
             .await?;
 
-        // Update user's last snort time
-        sqlx::query(
-            "INSERT INTO user_snort_cooldowns (user_id, last_snort_time) VALUES (?, NOW()) 
-             ON DUPLICATE KEY UPDATE last_snort_time = NOW()",
-        )
-        .bind(user_id as i64)
-        .execute(&self.pool)
-        .await?;
-
         // Get the new count
         let count = sqlx::query_scalar::<_, i64>("SELECT count FROM snort_counter WHERE id = 1")
             .fetch_one(&self.pool)
@@ -497,97 +1473,243 @@ impl Database {
         Ok(count)
     }
 
-    pub async fn get_user_last_snort_time(&self, user_id: u64) -> Result<Option<DateTime<Utc>>> {
-        let result = sqlx::query_scalar::<_, DateTime<Utc>>(
-            "SELECT last_snort_time FROM user_snort_cooldowns WHERE user_id = ?",
-        )
-        .bind(user_id as i64)
-        .fetch_optional(&self.pool)
-        .await?;
+    pub async fn get_snort_count(&self) -> Result<i64> {
+        let count = sqlx::query_scalar::<_, i64>("SELECT count FROM snort_counter WHERE id = 1")
+            .fetch_optional(&self.pool)
+            .await?;
 
-        Ok(result)
+        Ok(count.unwrap_or(0))
     }
 
-    pub async fn get_snort_cooldown_seconds(&self) -> Result<u64> {
-        let result = self
-            .get_setting("snort_cooldown_seconds")
-            .await?
-            .unwrap_or_else(|| "30".to_string())
-            .parse::<u64>()
-            .unwrap_or(30);
+    /// Zeroes the global snort counter, returning the count it had before the reset.
+    pub async fn reset_snort_counter(&self) -> Result<i64> {
+        let mut tx = self.pool.begin().await?;
 
-        Ok(result)
+        let previous =
+            sqlx::query_scalar::<_, i64>("SELECT count FROM snort_counter WHERE id = 1 FOR UPDATE")
+                .fetch_optional(&mut *tx)
+                .await?
+                .unwrap_or(0);
+
+        sqlx::query("UPDATE snort_counter SET count = 0 WHERE id = 1")
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(previous)
     }
 
-    pub async fn is_channel_scanned(&self, channel_id: u64) -> Result<bool> {
-        let result = sqlx::query_scalar::<_, i64>(
-            "SELECT COUNT(*) FROM channel_scan_history WHERE channel_id = ?",
+    /// Checks the per-user cooldown for `command` and, when it has passed, starts a new
+    /// one. Returns whether the use is allowed and, if not, the seconds left to wait.
+    /// Elapsed time is measured by the database so bot and server clocks can't disagree.
+    pub async fn check_and_update_cooldown(
+        &self,
+        user_id: u64,
+        command: &str,
+        seconds: u64,
+    ) -> Result<(bool, u64)> {
+        let mut tx = self.pool.begin().await?;
+
+        let elapsed = sqlx::query_scalar::<_, i64>(
+            "SELECT TIMESTAMPDIFF(SECOND, last_used, NOW()) FROM command_cooldowns
+             WHERE user_id = ? AND command = ? FOR UPDATE",
         )
-        .bind(channel_id as i64)
-        .fetch_one(&self.pool)
+        .bind(user_id as i64)
+        .bind(command)
+        .fetch_optional(&mut *tx)
         .await?;
 
-        Ok(result > 0)
+        if let Some(elapsed) = elapsed {
+            let elapsed = elapsed.max(0) as u64;
+            if elapsed < seconds {
+                tx.rollback().await?;
+                return Ok((false, seconds - elapsed));
+            }
+        }
+
+        sqlx::query(
+            "INSERT INTO command_cooldowns (user_id, command, last_used) VALUES (?, ?, NOW())
+             ON DUPLICATE KEY UPDATE last_used = NOW()",
+        )
+        .bind(user_id as i64)
+        .bind(command)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok((true, 0))
     }
 
-    pub async fn mark_channel_scanned(
+    /// Sets the emoji the bot reacts with when `keyword` appears in a guild, replacing
+    /// any emoji already configured for it. Keywords are stored lowercase.
+    pub async fn set_keyword_reaction(
         &self,
-        channel_id: u64,
         guild_id: u64,
-        oldest_message_id: Option<u64>,
-        messages_scanned: u32,
+        keyword: &str,
+        emoji: &str,
+        added_by: u64,
     ) -> Result<()> {
         sqlx::query(
             r#"
-            INSERT INTO channel_scan_history (channel_id, guild_id, scan_completed_at, oldest_message_id, messages_scanned)
-            VALUES (?, ?, NOW(), ?, ?)
+            INSERT INTO keyword_reactions (guild_id, keyword, emoji, added_by)
+            VALUES (?, ?, ?, ?)
+            ON DUPLICATE KEY UPDATE emoji = VALUES(emoji), added_by = VALUES(added_by)
             "#,
         )
-        .bind(channel_id as i64)
         .bind(guild_id as i64)
-        .bind(oldest_message_id.map(|id| id as i64))
-        .bind(messages_scanned as i32)
+        .bind(keyword.to_lowercase())
+        .bind(emoji)
+        .bind(added_by as i64)
         .execute(&self.pool)
         .await?;
 
         Ok(())
     }
 
-    pub async fn get_unscanned_channels(&self) -> Result<Vec<(u64, u64)>> {
-        // This method will be used by the background job to find channels that haven't been scanned
-        // Using runtime query to avoid compile-time verification issues
-        let results: Vec<(i64, i64)> = sqlx::query_as(
-            r#"
-            SELECT DISTINCT mc.channel_id, mc.guild_id
-            FROM (
-                SELECT DISTINCT channel_id, 
-                       (SELECT guild_id FROM channel_logs WHERE channel_id = ml.channel_id LIMIT 1) as guild_id
-                FROM message_logs ml
-                UNION
-                SELECT DISTINCT channel_id, guild_id
-                FROM channel_logs
-            ) mc
-            LEFT JOIN channel_scan_history csh ON mc.channel_id = csh.channel_id
-            WHERE csh.channel_id IS NULL AND mc.guild_id IS NOT NULL
-            "#
-        )
-        .fetch_all(&self.pool)
-        .await?;
+    /// Removes a keyword reaction, returning whether one existed.
+    pub async fn remove_keyword_reaction(&self, guild_id: u64, keyword: &str) -> Result<bool> {
+        let result =
+            sqlx::query("DELETE FROM keyword_reactions WHERE guild_id = ? AND keyword = ?")
+                .bind(guild_id as i64)
+                .bind(keyword.to_lowercase())
+                .execute(&self.pool)
+                .await?;
 
-        Ok(results
-            .into_iter()
-            .map(|(channel_id, guild_id)| (channel_id as u64, guild_id as u64))
-            .collect())
+        Ok(result.rows_affected() > 0)
     }
 
-    // Poll tracking methods
-    pub async fn log_poll_created(
+    /// Adds or updates a watched keyword for a guild. Keywords are stored lowercase and may
+    /// contain `*` wildcards.
+    pub async fn set_watched_keyword(
         &self,
-        poll_id: &str,
-        message_id: u64,
-        channel_id: u64,
         guild_id: u64,
-        creator_id: u64,
+        keyword: &str,
+        severity: &str,
+        added_by: u64,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO watched_keywords (guild_id, keyword, severity, added_by)
+            VALUES (?, ?, ?, ?)
+            ON DUPLICATE KEY UPDATE severity = VALUES(severity), added_by = VALUES(added_by)
+            "#,
+        )
+        .bind(guild_id as i64)
+        .bind(keyword.to_lowercase())
+        .bind(severity)
+        .bind(added_by as i64)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Removes a watched keyword, returning whether one existed.
+    pub async fn remove_watched_keyword(&self, guild_id: u64, keyword: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM watched_keywords WHERE guild_id = ? AND keyword = ?")
+            .bind(guild_id as i64)
+            .bind(keyword.to_lowercase())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Returns a guild's (keyword, severity) watchwords in the order they were added.
+    pub async fn get_watched_keywords(&self, guild_id: u64) -> Result<Vec<(String, String)>> {
+        let rows = sqlx::query_as::<_, (String, String)>(
+            "SELECT keyword, severity FROM watched_keywords WHERE guild_id = ? ORDER BY id",
+        )
+        .bind(guild_id as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// Returns a guild's (keyword, emoji) reactions in the order they were added.
+    pub async fn get_keyword_reactions(&self, guild_id: u64) -> Result<Vec<(String, String)>> {
+        let rows = sqlx::query_as::<_, (String, String)>(
+            "SELECT keyword, emoji FROM keyword_reactions WHERE guild_id = ? ORDER BY id",
+        )
+        .bind(guild_id as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows)
+    }
+
+    pub async fn is_channel_scanned(&self, channel_id: u64) -> Result<bool> {
+        let result = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM channel_scan_history WHERE channel_id = ?",
+        )
+        .bind(channel_id as i64)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(result > 0)
+    }
+
+    pub async fn mark_channel_scanned(
+        &self,
+        channel_id: u64,
+        guild_id: u64,
+        oldest_message_id: Option<u64>,
+        messages_scanned: u32,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO channel_scan_history (channel_id, guild_id, scan_completed_at, oldest_message_id, messages_scanned)
+            VALUES (?, ?, NOW(), ?, ?)
+            "#,
+        )
+        .bind(channel_id as i64)
+        .bind(guild_id as i64)
+        .bind(oldest_message_id.map(|id| id as i64))
+        .bind(messages_scanned as i32)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    #[allow(dead_code)]
+    pub async fn get_unscanned_channels(&self) -> Result<Vec<(u64, u64)>> {
+        // This method will be used by the background job to find channels that haven't been scanned
+        // Using runtime query to avoid compile-time verification issues
+        let results: Vec<(i64, i64)> = sqlx::query_as(
+            r#"
+            SELECT DISTINCT mc.channel_id, mc.guild_id
+            FROM (
+                SELECT DISTINCT channel_id, 
+                       (SELECT guild_id FROM channel_logs WHERE channel_id = ml.channel_id LIMIT 1) as guild_id
+                FROM message_logs ml
+                UNION
+                SELECT DISTINCT channel_id, guild_id
+                FROM channel_logs
+            ) mc
+            LEFT JOIN channel_scan_history csh ON mc.channel_id = csh.channel_id
+            WHERE csh.channel_id IS NULL AND mc.guild_id IS NOT NULL
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(results
+            .into_iter()
+            .map(|(channel_id, guild_id)| (channel_id as u64, guild_id as u64))
+            .collect())
+    }
+
+    // Poll tracking methods
+    #[allow(clippy::too_many_arguments)]
+    pub async fn log_poll_created(
+        &self,
+        poll_id: &str,
+        message_id: u64,
+        channel_id: u64,
+        guild_id: u64,
+        creator_id: u64,
         question: &str,
         expires_at: Option<DateTime<Utc>>,
         is_multiselect: bool,
@@ -688,6 +1810,29 @@ impl Database {
         Ok(())
     }
 
+    pub async fn snapshot_poll_results(&self, poll_id: &str) -> Result<u64> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO poll_results (poll_id, answer_id, answer_text, vote_count)
+            SELECT pa.poll_id, pa.answer_id, pa.answer_text, COUNT(pv.id)
+            FROM poll_answers pa
+            LEFT JOIN poll_votes pv ON pv.poll_id = pa.poll_id AND pv.answer_id = pa.answer_id
+            WHERE pa.poll_id = ?
+            GROUP BY pa.poll_id, pa.answer_id, pa.answer_text
+            ON DUPLICATE KEY UPDATE
+                answer_text = VALUES(answer_text),
+                vote_count = VALUES(vote_count),
+                recorded_at = NOW()
+            "#,
+        )
+        .bind(poll_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    #[allow(dead_code)]
     pub async fn get_poll_votes(&self, poll_id: &str, user_id: u64) -> Result<Vec<u32>> {
         let votes: Vec<(u32,)> = sqlx::query_as(
             r#"
@@ -705,6 +1850,7 @@ impl Database {
     }
 
     // Event tracking methods
+    #[allow(clippy::too_many_arguments)]
     pub async fn log_event_created(
         &self,
         event_id: u64,
@@ -725,6 +1871,7 @@ impl Database {
             ON DUPLICATE KEY UPDATE
                 name = VALUES(name),
                 description = VALUES(description),
+                reminded = IF(start_time <> VALUES(start_time), FALSE, reminded),
                 start_time = VALUES(start_time),
                 end_time = VALUES(end_time),
                 location = VALUES(location),
@@ -747,6 +1894,111 @@ impl Database {
         Ok(())
     }
 
+    /// Scheduled events starting within the next `minutes` whose interested users
+    /// haven't been reminded yet. Cancelled and deleted events are skipped.
+    pub async fn get_events_needing_reminder(
+        &self,
+        minutes: i64,
+    ) -> Result<Vec<(u64, u64, String, DateTime<Utc>, Option<String>)>> {
+        let now = Utc::now();
+        type EventRow = (i64, i64, String, DateTime<Utc>, Option<String>);
+        let rows: Vec<EventRow> = sqlx::query_as(
+            r#"
+            SELECT el.event_id, el.guild_id, el.name, el.start_time, el.location
+            FROM event_logs el
+            WHERE el.status = 'scheduled'
+            AND el.reminded = FALSE
+            AND el.start_time > ?
+            AND el.start_time <= ?
+            AND NOT EXISTS (
+                SELECT 1 FROM event_update_logs eul
+                WHERE eul.event_id = el.event_id
+                AND eul.field_name = 'status'
+                AND eul.new_value = 'deleted'
+            )
+            "#,
+        )
+        .bind(now)
+        .bind(now + chrono::Duration::minutes(minutes))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(event_id, guild_id, name, start_time, location)| {
+                (event_id as u64, guild_id as u64, name, start_time, location)
+            })
+            .collect())
+    }
+
+    pub async fn get_upcoming_events(
+        &self,
+        guild_id: u64,
+        limit: u32,
+    ) -> Result<Vec<(u64, String, DateTime<Utc>, Option<String>, i64)>> {
+        type UpcomingEventRow = (i64, String, DateTime<Utc>, Option<String>, i64);
+        let rows: Vec<UpcomingEventRow> = sqlx::query_as(
+            r#"
+            SELECT 
+                el.event_id,
+                el.name,
+                el.start_time,
+                el.location,
+                CAST(COUNT(ei.id) AS SIGNED) as interested_count
+            FROM event_logs el
+            LEFT JOIN event_interests ei 
+                ON ei.event_id = el.event_id AND ei.interest_type <> 'not_interested'
+            WHERE el.guild_id = ?
+            AND el.start_time > ?
+            AND el.status = 'scheduled'
+            AND NOT EXISTS (
+                SELECT 1 FROM event_update_logs eul
+                WHERE eul.event_id = el.event_id
+                AND eul.field_name = 'status'
+                AND eul.new_value = 'deleted'
+            )
+            GROUP BY el.event_id, el.name, el.start_time, el.location
+            ORDER BY el.start_time ASC
+            LIMIT ?
+            "#,
+        )
+        .bind(guild_id as i64)
+        .bind(Utc::now())
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(event_id, name, start_time, location, interested)| {
+                (event_id as u64, name, start_time, location, interested)
+            })
+            .collect())
+    }
+
+    pub async fn get_event_interested_users(&self, event_id: u64) -> Result<Vec<u64>> {
+        let users: Vec<i64> = sqlx::query_scalar(
+            r#"
+            SELECT user_id FROM event_interests
+            WHERE event_id = ? AND interest_type <> 'not_interested'
+            "#,
+        )
+        .bind(event_id as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(users.into_iter().map(|id| id as u64).collect())
+    }
+
+    pub async fn mark_event_reminded(&self, event_id: u64) -> Result<()> {
+        sqlx::query("UPDATE event_logs SET reminded = TRUE WHERE event_id = ?")
+            .bind(event_id as i64)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
     pub async fn log_event_interest(
         &self,
         event_id: u64,
@@ -822,6 +2074,7 @@ impl Database {
         Ok(result.rows_affected())
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn log_media_recommendation(
         &self,
         message_id: u64,
@@ -832,25 +2085,34 @@ impl Database {
         title: &str,
         url: Option<&str>,
         confidence: f32,
+        detector_version: u32,
         message_timestamp: DateTime<Utc>,
     ) -> Result<()> {
-        sqlx::query(
-            r#"
-            INSERT IGNORE INTO media_recommendations 
-            (message_id, user_id, channel_id, guild_id, media_type, title, url, confidence_score, message_timestamp)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
-            "#,
-        )
-        .bind(message_id as i64)
-        .bind(user_id as i64)
-        .bind(channel_id as i64)
-        .bind(guild_id as i64)
-        .bind(media_type)
-        .bind(title)
-        .bind(url)
-        .bind(confidence)
-        .bind(message_timestamp)
-        .execute(&self.pool)
+        query_with_retry("log_media_recommendation", || {
+            sqlx::query(
+                r#"
+                INSERT INTO media_recommendations 
+                (message_id, user_id, channel_id, guild_id, media_type, title, normalized_title, url, confidence_score, detector_version, message_timestamp)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                ON DUPLICATE KEY UPDATE
+                    url = VALUES(url),
+                    confidence_score = VALUES(confidence_score),
+                    detector_version = VALUES(detector_version)
+                "#,
+            )
+            .bind(message_id as i64)
+            .bind(user_id as i64)
+            .bind(channel_id as i64)
+            .bind(guild_id as i64)
+            .bind(media_type)
+            .bind(title)
+            .bind(normalize_title(title))
+            .bind(url)
+            .bind(confidence)
+            .bind(detector_version)
+            .bind(message_timestamp)
+            .execute(&self.pool)
+        })
         .await?;
 
         Ok(())
@@ -882,177 +2144,602 @@ impl Database {
             WHERE id = 1
             "#,
         )
-        .bind(last_message_id as i64)
-        .bind(messages_scanned as i32)
-        .bind(recommendations_found as i32)
+        .bind(last_message_id as i64)
+        .bind(messages_scanned as i32)
+        .bind(recommendations_found as i32)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Rewinds the media scan checkpoint so the next scan starts from the first message.
+    pub async fn reset_media_scan_checkpoint(&self) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE media_scan_checkpoint
+            SET last_scanned_message_id = 0,
+                last_scan_time = NOW()
+            WHERE id = 1
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Deletes recommendations produced by a detector older than `current_version`.
+    pub async fn delete_outdated_recommendations(&self, current_version: u32) -> Result<u64> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM media_recommendations
+            WHERE detector_version < ?
+            "#,
+        )
+        .bind(current_version)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    pub async fn get_unscanned_messages(
+        &self,
+        last_id: u64,
+        limit: u32,
+    ) -> Result<Vec<(u64, u64, u64, u64, String, DateTime<Utc>)>> {
+        let messages: Vec<(i64, i64, i64, i64, String, DateTime<Utc>)> = sqlx::query_as(
+            r#"
+            SELECT ml.message_id, ml.user_id, ml.channel_id, 
+                   COALESCE(cl.guild_id, 0) as guild_id,
+                   ml.content, ml.timestamp
+            FROM message_logs ml
+            LEFT JOIN channel_logs cl ON ml.channel_id = cl.channel_id 
+                AND cl.action = 'create'
+            WHERE ml.message_id > ? 
+                AND ml.content IS NOT NULL 
+                AND ml.content != ''
+            ORDER BY ml.message_id ASC
+            LIMIT ?
+            "#,
+        )
+        .bind(last_id as i64)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(messages
+            .into_iter()
+            .map(
+                |(msg_id, user_id, channel_id, guild_id, content, timestamp)| {
+                    (
+                        msg_id as u64,
+                        user_id as u64,
+                        channel_id as u64,
+                        guild_id as u64,
+                        content,
+                        timestamp,
+                    )
+                },
+            )
+            .collect())
+    }
+
+    // Watchlist methods
+    pub async fn add_to_watchlist(
+        &self,
+        user_id: u64,
+        media_type: &str,
+        title: &str,
+        url: Option<&str>,
+        priority: Option<i32>,
+        notes: Option<&str>,
+    ) -> Result<()> {
+        upsert_watchlist_item(
+            &self.pool, user_id, media_type, title, url, priority, None, notes,
+        )
+        .await
+    }
+
+    /// Adds every `(type, title, url, priority, status, notes)` row to a user's
+    /// watchlist in one transaction, so a failed import leaves the list untouched.
+    pub async fn import_watchlist(
+        &self,
+        user_id: u64,
+        items: &[WatchlistExportItem],
+    ) -> Result<usize> {
+        let mut tx = self.pool.begin().await?;
+
+        for (media_type, title, url, priority, status, notes) in items {
+            upsert_watchlist_item(
+                &mut *tx,
+                user_id,
+                media_type,
+                title,
+                url.as_deref(),
+                Some(*priority),
+                Some(status),
+                notes.as_deref(),
+            )
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(items.len())
+    }
+
+    pub async fn remove_from_watchlist(
+        &self,
+        user_id: u64,
+        media_type: &str,
+        title: &str,
+    ) -> DbResult<()> {
+        let result = sqlx::query(
+            "DELETE FROM user_watchlist WHERE user_id = ? AND media_type = ? AND title = ?",
+        )
+        .bind(user_id as i64)
+        .bind(media_type)
+        .bind(title)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(DbError::NotFound);
+        }
+
+        Ok(())
+    }
+
+    pub async fn update_watchlist_priority(
+        &self,
+        user_id: u64,
+        media_type: &str,
+        title: &str,
+        priority: i32,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE user_watchlist 
+            SET priority = ?, updated_at = NOW()
+            WHERE user_id = ? AND media_type = ? AND title = ?
+            "#,
+        )
+        .bind(priority)
+        .bind(user_id as i64)
+        .bind(media_type)
+        .bind(title)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn set_watchlist_reminder(
+        &self,
+        user_id: u64,
+        media_type: &str,
+        title: &str,
+        remind_at: DateTime<Utc>,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE user_watchlist 
+            SET remind_at = ?, updated_at = NOW()
+            WHERE user_id = ? AND media_type = ? AND title = ?
+            "#,
+        )
+        .bind(remind_at)
+        .bind(user_id as i64)
+        .bind(media_type)
+        .bind(title)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn get_due_watchlist_reminders(
+        &self,
+        limit: u32,
+    ) -> Result<Vec<(i32, u64, String, String, Option<String>)>> {
+        let items: Vec<(i32, i64, String, String, Option<String>)> = sqlx::query_as(
+            r#"
+            SELECT id, user_id, media_type, title, url
+            FROM user_watchlist
+            WHERE remind_at IS NOT NULL AND remind_at <= ?
+            ORDER BY remind_at ASC
+            LIMIT ?
+            "#,
+        )
+        .bind(Utc::now())
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(items
+            .into_iter()
+            .map(|(id, user_id, media_type, title, url)| {
+                (id, user_id as u64, media_type, title, url)
+            })
+            .collect())
+    }
+
+    pub async fn clear_watchlist_reminder(&self, watchlist_id: i32) -> Result<()> {
+        sqlx::query("UPDATE user_watchlist SET remind_at = NULL WHERE id = ?")
+            .bind(watchlist_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Deletes a user's watchlist items, optionally only those of one media type
+    /// and/or status. Returns how many were removed.
+    pub async fn clear_user_watchlist(
+        &self,
+        user_id: u64,
+        media_type_filter: Option<&str>,
+        status_filter: Option<&str>,
+    ) -> Result<u64> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM user_watchlist
+            WHERE user_id = ?
+                AND (? IS NULL OR media_type = ?)
+                AND (? IS NULL OR status = ?)
+            "#,
+        )
+        .bind(user_id as i64)
+        .bind(media_type_filter)
+        .bind(media_type_filter)
+        .bind(status_filter)
+        .bind(status_filter)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// All titles of one media type on a user's watchlist, for duplicate checks.
+    pub async fn get_watchlist_titles(
+        &self,
+        user_id: u64,
+        media_type: &str,
+    ) -> Result<Vec<String>> {
+        let titles: Vec<String> = sqlx::query_scalar(
+            "SELECT title FROM user_watchlist WHERE user_id = ? AND media_type = ?",
+        )
+        .bind(user_id as i64)
+        .bind(media_type)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(titles)
+    }
+
+    /// Active watchlist items for a user. `sort` is one of `priority` (default), `recent`,
+    /// `alphabetical` or `type`; unknown values fall back to priority order.
+    pub async fn get_user_watchlist(
+        &self,
+        user_id: u64,
+        limit: u32,
+        sort: &str,
+    ) -> Result<Vec<(String, String, Option<String>, i32, String)>> {
+        // Only these fixed clauses ever reach the query, never the user's input
+        let order_by = match sort {
+            "recent" => "updated_at DESC",
+            "alphabetical" => "title ASC",
+            "type" => "media_type ASC, priority DESC, title ASC",
+            _ => "priority DESC, updated_at DESC",
+        };
+
+        let query = format!(
+            r#"
+            SELECT media_type, title, url, priority, status
+            FROM user_watchlist
+            WHERE user_id = ? AND status IN ('plan_to_watch', 'watching')
+            ORDER BY {}
+            LIMIT ?
+            "#,
+            order_by
+        );
+
+        let items: Vec<(String, String, Option<String>, i32, String)> = sqlx::query_as(&query)
+            .bind(user_id as i64)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(items)
+    }
+
+    pub async fn get_top_recommendations(
+        &self,
+        limit: u32,
+        days: i32,
+        media_type: Option<&str>,
+        min_mentions: u32,
+    ) -> Result<Vec<(String, String, f32, i64, Option<String>)>> {
+        let cutoff = chrono::Utc::now() - chrono::Duration::days(days as i64);
+
+        let query = if let Some(media_type) = media_type {
+            sqlx::query_as(
+                r#"
+                SELECT 
+                    mr.media_type,
+                    (
+                        SELECT v.title FROM media_recommendations v
+                        WHERE v.media_type = mr.media_type AND v.normalized_title = mr.normalized_title
+                        GROUP BY v.title
+                        ORDER BY COUNT(*) DESC, MAX(v.message_timestamp) DESC
+                        LIMIT 1
+                    ) as title,
+                    AVG(mr.confidence_score) as avg_confidence,
+                    COUNT(*) as mention_count,
+                    MAX(mr.url) as sample_url
+                FROM media_recommendations mr
+                WHERE mr.message_timestamp > ? AND mr.media_type = ?
+                GROUP BY mr.media_type, mr.normalized_title
+                HAVING COUNT(*) >= ?
+                ORDER BY COUNT(*) DESC, AVG(mr.confidence_score) DESC
+                LIMIT ?
+                "#,
+            )
+            .bind(cutoff)
+            .bind(media_type)
+            .bind(min_mentions)
+            .bind(limit)
+        } else {
+            sqlx::query_as(
+                r#"
+                SELECT 
+                    mr.media_type,
+                    (
+                        SELECT v.title FROM media_recommendations v
+                        WHERE v.media_type = mr.media_type AND v.normalized_title = mr.normalized_title
+                        GROUP BY v.title
+                        ORDER BY COUNT(*) DESC, MAX(v.message_timestamp) DESC
+                        LIMIT 1
+                    ) as title,
+                    AVG(mr.confidence_score) as avg_confidence,
+                    COUNT(*) as mention_count,
+                    MAX(mr.url) as sample_url
+                FROM media_recommendations mr
+                WHERE mr.message_timestamp > ?
+                GROUP BY mr.media_type, mr.normalized_title
+                HAVING COUNT(*) >= ?
+                ORDER BY COUNT(*) DESC, AVG(mr.confidence_score) DESC
+                LIMIT ?
+                "#,
+            )
+            .bind(cutoff)
+            .bind(min_mentions)
+            .bind(limit)
+        };
+
+        let items: Vec<(String, String, f32, i64, Option<String>)> =
+            query.fetch_all(&self.pool).await?;
+
+        Ok(items)
+    }
+
+    /// Opts a user out of media recommendation tracking. Returns false if they already were.
+    pub async fn add_media_tracking_optout(&self, user_id: u64) -> Result<bool> {
+        let result = sqlx::query("INSERT IGNORE INTO media_tracking_optout (user_id) VALUES (?)")
+            .bind(user_id as i64)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Opts a user back in. Returns false if they weren't opted out.
+    pub async fn remove_media_tracking_optout(&self, user_id: u64) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM media_tracking_optout WHERE user_id = ?")
+            .bind(user_id as i64)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// When the user opted out of media recommendation tracking, if they have.
+    pub async fn get_media_tracking_optout(&self, user_id: u64) -> Result<Option<DateTime<Utc>>> {
+        let opted_out_at = sqlx::query_scalar::<_, DateTime<Utc>>(
+            "SELECT opted_out_at FROM media_tracking_optout WHERE user_id = ?",
+        )
+        .bind(user_id as i64)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(opted_out_at)
+    }
+
+    /// Every opted-out user, for batch jobs that would otherwise query per message.
+    pub async fn get_media_tracking_optouts(&self) -> Result<std::collections::HashSet<u64>> {
+        let user_ids = sqlx::query_scalar::<_, i64>("SELECT user_id FROM media_tracking_optout")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(user_ids.into_iter().map(|id| id as u64).collect())
+    }
+
+    /// Adds a title to the media detection blacklist. Returns false if it was already there.
+    /// Terms are stored normalized (see `media_detector::blacklist_key`).
+    pub async fn add_media_blacklist_term(&self, term: &str, added_by: u64) -> Result<bool> {
+        let result = sqlx::query(
+            "INSERT IGNORE INTO media_detection_blacklist (term, added_by) VALUES (?, ?)",
+        )
+        .bind(term)
+        .bind(added_by as i64)
         .execute(&self.pool)
         .await?;
 
-        Ok(())
+        Ok(result.rows_affected() > 0)
     }
 
-    pub async fn get_unscanned_messages(
-        &self,
-        last_id: u64,
-        limit: u32,
-    ) -> Result<Vec<(u64, u64, u64, u64, String, DateTime<Utc>)>> {
-        let messages: Vec<(i64, i64, i64, i64, String, DateTime<Utc>)> = sqlx::query_as(
-            r#"
-            SELECT ml.message_id, ml.user_id, ml.channel_id, 
-                   COALESCE(cl.guild_id, 0) as guild_id,
-                   ml.content, ml.timestamp
-            FROM message_logs ml
-            LEFT JOIN channel_logs cl ON ml.channel_id = cl.channel_id 
-                AND cl.action = 'create'
-            WHERE ml.message_id > ? 
-                AND ml.content IS NOT NULL 
-                AND ml.content != ''
-            ORDER BY ml.message_id ASC
-            LIMIT ?
-            "#,
+    /// Removes a blacklisted title, returning whether one existed.
+    pub async fn remove_media_blacklist_term(&self, term: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM media_detection_blacklist WHERE term = ?")
+            .bind(term)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Every blacklisted title in the order they were added.
+    pub async fn get_media_blacklist(&self) -> Result<Vec<String>> {
+        let terms = sqlx::query_scalar::<_, String>(
+            "SELECT term FROM media_detection_blacklist ORDER BY id",
         )
-        .bind(last_id as i64)
-        .bind(limit)
         .fetch_all(&self.pool)
         .await?;
 
-        Ok(messages
-            .into_iter()
-            .map(
-                |(msg_id, user_id, channel_id, guild_id, content, timestamp)| {
-                    (
-                        msg_id as u64,
-                        user_id as u64,
-                        channel_id as u64,
-                        guild_id as u64,
-                        content,
-                        timestamp,
-                    )
-                },
-            )
-            .collect())
+        Ok(terms)
     }
 
-    // Watchlist methods
-    pub async fn add_to_watchlist(
-        &self,
-        user_id: u64,
-        media_type: &str,
-        title: &str,
-        url: Option<&str>,
-        priority: Option<i32>,
-        notes: Option<&str>,
-    ) -> Result<()> {
+    /// Deletes already-recorded recommendations whose title matches a blacklisted term,
+    /// ignoring case. Returns how many rows were removed.
+    pub async fn purge_media_recommendations_titled(&self, term: &str) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM media_recommendations WHERE LOWER(title) = ?")
+            .bind(term)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Cached store name for a Steam app ID, if it has been looked up before.
+    pub async fn get_steam_app_name(&self, app_id: u64) -> Result<Option<String>> {
+        let name =
+            sqlx::query_scalar::<_, String>("SELECT name FROM steam_app_cache WHERE app_id = ?")
+                .bind(app_id as i64)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        Ok(name)
+    }
+
+    pub async fn set_steam_app_name(&self, app_id: u64, name: &str) -> Result<()> {
         sqlx::query(
             r#"
-            INSERT INTO user_watchlist (user_id, media_type, title, url, priority, notes)
-            VALUES (?, ?, ?, ?, ?, ?)
-            ON DUPLICATE KEY UPDATE 
-                url = COALESCE(VALUES(url), url),
-                priority = COALESCE(VALUES(priority), priority),
-                notes = COALESCE(VALUES(notes), notes),
-                updated_at = NOW()
+            INSERT INTO steam_app_cache (app_id, name)
+            VALUES (?, ?)
+            ON DUPLICATE KEY UPDATE name = VALUES(name), fetched_at = CURRENT_TIMESTAMP
             "#,
         )
-        .bind(user_id as i64)
-        .bind(media_type)
-        .bind(title)
-        .bind(url)
-        .bind(priority.unwrap_or(50))
-        .bind(notes)
+        .bind(app_id as i64)
+        .bind(name)
         .execute(&self.pool)
         .await?;
 
         Ok(())
     }
 
-    pub async fn remove_from_watchlist(
-        &self,
-        user_id: u64,
-        media_type: &str,
-        title: &str,
-    ) -> Result<bool> {
-        let result = sqlx::query(
-            "DELETE FROM user_watchlist WHERE user_id = ? AND media_type = ? AND title = ?",
+    /// Cache paths of the user's attachments, read before `delete_user_data` drops the rows
+    /// so the files can be unlinked afterwards.
+    pub async fn get_user_attachment_paths(&self, user_id: u64) -> Result<Vec<String>> {
+        let paths = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT DISTINCT ma.local_path FROM message_attachments ma
+            JOIN message_logs ml ON ml.message_id = ma.message_id
+            WHERE ml.user_id = ? AND ma.local_path IS NOT NULL
+            "#,
         )
         .bind(user_id as i64)
-        .bind(media_type)
-        .bind(title)
-        .execute(&self.pool)
+        .fetch_all(&self.pool)
         .await?;
 
-        Ok(result.rows_affected() > 0)
+        Ok(paths)
     }
 
-    pub async fn update_watchlist_priority(
-        &self,
-        user_id: u64,
-        media_type: &str,
-        title: &str,
-        priority: i32,
-    ) -> Result<bool> {
-        let result = sqlx::query(
+    /// Erases everything stored about one user's activity in a single transaction and
+    /// returns the rows removed per table. Edit history and attachment records go first
+    /// since they are only linked to the user through their `message_logs` rows.
+    pub async fn delete_user_data(&self, user_id: u64) -> Result<Vec<(&'static str, u64)>> {
+        let user_id = user_id as i64;
+        let mut tx = self.pool.begin().await?;
+        let mut deleted = Vec::new();
+
+        let edits = sqlx::query(
             r#"
-            UPDATE user_watchlist 
-            SET priority = ?, updated_at = NOW()
-            WHERE user_id = ? AND media_type = ? AND title = ?
+            DELETE mel FROM message_edit_logs mel
+            JOIN message_logs ml ON ml.message_id = mel.message_id
+            WHERE ml.user_id = ?
             "#,
         )
-        .bind(priority)
-        .bind(user_id as i64)
-        .bind(media_type)
-        .bind(title)
-        .execute(&self.pool)
+        .bind(user_id)
+        .execute(&mut *tx)
         .await?;
+        deleted.push(("message_edit_logs", edits.rows_affected()));
 
-        Ok(result.rows_affected() > 0)
-    }
-
-    pub async fn get_user_watchlist(
-        &self,
-        user_id: u64,
-        limit: u32,
-    ) -> Result<Vec<(String, String, Option<String>, i32, String)>> {
-        let items: Vec<(String, String, Option<String>, i32, String)> = sqlx::query_as(
+        let attachments = sqlx::query(
             r#"
-            SELECT media_type, title, url, priority, status
-            FROM user_watchlist
-            WHERE user_id = ? AND status IN ('plan_to_watch', 'watching')
-            ORDER BY priority DESC, updated_at DESC
-            LIMIT ?
+            DELETE ma FROM message_attachments ma
+            JOIN message_logs ml ON ml.message_id = ma.message_id
+            WHERE ml.user_id = ?
             "#,
         )
-        .bind(user_id as i64)
-        .bind(limit)
-        .fetch_all(&self.pool)
+        .bind(user_id)
+        .execute(&mut *tx)
         .await?;
+        deleted.push(("message_attachments", attachments.rows_affected()));
+
+        for table in [
+            "message_logs",
+            "dm_logs",
+            "media_recommendations",
+            "user_watchlist",
+            "global_watchlist_votes",
+            "poll_votes",
+            "bot_response_logs",
+        ] {
+            let result = sqlx::query(&format!("DELETE FROM {} WHERE user_id = ?", table))
+                .bind(user_id)
+                .execute(&mut *tx)
+                .await?;
+            deleted.push((table, result.rows_affected()));
+        }
 
-        Ok(items)
+        tx.commit().await?;
+        Ok(deleted)
     }
 
-    pub async fn get_top_recommendations(
+    /// What one user has recommended in the last `days`, grouped by title and ranked by
+    /// how often they mentioned it: (media_type, title, mentions, sample_url, last_mentioned).
+    pub async fn get_recommendations_by_user(
         &self,
-        limit: u32,
+        user_id: u64,
         days: i32,
-    ) -> Result<Vec<(String, String, f32, i64, Option<String>)>> {
+        limit: u32,
+    ) -> Result<Vec<(String, String, i64, Option<String>, DateTime<Utc>)>> {
         let cutoff = chrono::Utc::now() - chrono::Duration::days(days as i64);
 
-        let items: Vec<(String, String, f32, i64, Option<String>)> = sqlx::query_as(
+        let items = sqlx::query_as(
             r#"
-            SELECT 
-                media_type,
-                title,
-                AVG(confidence_score) as avg_confidence,
+            SELECT
+                mr.media_type,
+                (
+                    SELECT v.title FROM media_recommendations v
+                    WHERE v.media_type = mr.media_type AND v.normalized_title = mr.normalized_title
+                    GROUP BY v.title
+                    ORDER BY COUNT(*) DESC, MAX(v.message_timestamp) DESC
+                    LIMIT 1
+                ) as title,
                 COUNT(*) as mention_count,
-                MAX(url) as sample_url
-            FROM media_recommendations
-            WHERE message_timestamp > ?
-            GROUP BY media_type, title
-            HAVING COUNT(*) >= 2
-            ORDER BY COUNT(*) DESC, AVG(confidence_score) DESC
+                MAX(mr.url) as sample_url,
+                MAX(mr.message_timestamp) as last_mentioned
+            FROM media_recommendations mr
+            WHERE mr.user_id = ? AND mr.message_timestamp > ? AND mr.title IS NOT NULL
+            GROUP BY mr.media_type, mr.normalized_title
+            ORDER BY COUNT(*) DESC, MAX(mr.message_timestamp) DESC
             LIMIT ?
             "#,
         )
+        .bind(user_id as i64)
         .bind(cutoff)
         .bind(limit)
         .fetch_all(&self.pool)
@@ -1071,18 +2758,25 @@ impl Database {
         let items: Vec<(String, String, f32, i64)> = sqlx::query_as(
             r#"
             SELECT 
-                media_type,
-                title,
-                AVG(confidence_score) as avg_confidence,
+                mr.media_type,
+                (
+                    SELECT v.title FROM media_recommendations v
+                    WHERE v.media_type = mr.media_type AND v.normalized_title = mr.normalized_title
+                    GROUP BY v.title
+                    ORDER BY COUNT(*) DESC, MAX(v.message_timestamp) DESC
+                    LIMIT 1
+                ) as title,
+                AVG(mr.confidence_score) as avg_confidence,
                 COUNT(*) as mention_count
-            FROM media_recommendations
-            WHERE title LIKE ?
-            GROUP BY media_type, title
-            ORDER BY COUNT(*) DESC, AVG(confidence_score) DESC
+            FROM media_recommendations mr
+            WHERE mr.title LIKE ? OR mr.normalized_title LIKE ?
+            GROUP BY mr.media_type, mr.normalized_title
+            ORDER BY COUNT(*) DESC, AVG(mr.confidence_score) DESC
             LIMIT ?
             "#,
         )
         .bind(search_pattern)
+        .bind(format!("%{}%", normalize_title(query)))
         .bind(limit)
         .fetch_all(&self.pool)
         .await?;
@@ -1090,22 +2784,18 @@ impl Database {
         Ok(items)
     }
 
-    pub async fn get_user_watchlist_full(
-        &self,
-        user_id: u64,
-    ) -> Result<Vec<(String, String, Option<String>, i32, String, Option<String>)>> {
-        let items: Vec<(String, String, Option<String>, i32, String, Option<String>)> =
-            sqlx::query_as(
-                r#"
+    pub async fn get_user_watchlist_full(&self, user_id: u64) -> Result<Vec<WatchlistExportItem>> {
+        let items: Vec<WatchlistExportItem> = sqlx::query_as(
+            r#"
             SELECT media_type, title, url, priority, status, notes
             FROM user_watchlist
             WHERE user_id = ?
             ORDER BY priority DESC, updated_at DESC
             "#,
-            )
-            .bind(user_id as i64)
-            .fetch_all(&self.pool)
-            .await?;
+        )
+        .bind(user_id as i64)
+        .fetch_all(&self.pool)
+        .await?;
 
         Ok(items)
     }
@@ -1113,24 +2803,41 @@ impl Database {
     pub async fn get_user_recommendations(
         &self,
         days: i32,
-    ) -> Result<Vec<(String, String, Option<String>, f32, i64, Vec<String>)>> {
-        let cutoff = chrono::Utc::now() - chrono::Duration::days(days as i64);
+    ) -> Result<Vec<RecommendationExportItem>> {
+        let now = chrono::Utc::now();
+        self.get_recommendations_in_range(now - chrono::Duration::days(days as i64), now)
+            .await
+    }
 
+    /// Recommendations mentioned from `from` (inclusive) up to `to` (exclusive), most
+    /// mentioned first, with up to 10 of the users who mentioned each one.
+    pub async fn get_recommendations_in_range(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<RecommendationExportItem>> {
         let items: Vec<(String, String, f32, i64, Option<String>)> = sqlx::query_as(
             r#"
             SELECT 
                 mr.media_type,
-                mr.title,
+                (
+                    SELECT v.title FROM media_recommendations v
+                    WHERE v.media_type = mr.media_type AND v.normalized_title = mr.normalized_title
+                    GROUP BY v.title
+                    ORDER BY COUNT(*) DESC, MAX(v.message_timestamp) DESC
+                    LIMIT 1
+                ) as title,
                 AVG(mr.confidence_score) as avg_confidence,
                 COUNT(*) as mention_count,
                 MAX(mr.url) as sample_url
             FROM media_recommendations mr
-            WHERE mr.message_timestamp > ?
-            GROUP BY mr.media_type, mr.title
+            WHERE mr.message_timestamp >= ? AND mr.message_timestamp < ?
+            GROUP BY mr.media_type, mr.normalized_title
             ORDER BY COUNT(*) DESC, AVG(mr.confidence_score) DESC
             "#,
         )
-        .bind(cutoff)
+        .bind(from)
+        .bind(to)
         .fetch_all(&self.pool)
         .await?;
 
@@ -1142,13 +2849,15 @@ impl Database {
                 SELECT DISTINCT u.username
                 FROM media_recommendations mr
                 JOIN users u ON mr.user_id = u.discord_user_id
-                WHERE mr.media_type = ? AND mr.title = ? AND mr.message_timestamp > ?
+                WHERE mr.media_type = ? AND mr.normalized_title = ?
+                  AND mr.message_timestamp >= ? AND mr.message_timestamp < ?
                 LIMIT 10
                 "#,
             )
             .bind(&media_type)
-            .bind(&title)
-            .bind(cutoff)
+            .bind(normalize_title(&title))
+            .bind(from)
+            .bind(to)
             .fetch_all(&self.pool)
             .await?;
 
@@ -1167,15 +2876,11 @@ impl Database {
         url: Option<&str>,
         description: Option<&str>,
         added_by: u64,
-    ) -> Result<u64> {
+    ) -> DbResult<u64> {
         let result = sqlx::query(
             r#"
             INSERT INTO global_watchlist (media_type, title, url, description, added_by)
             VALUES (?, ?, ?, ?, ?)
-            ON DUPLICATE KEY UPDATE 
-                url = COALESCE(VALUES(url), url),
-                description = COALESCE(VALUES(description), description),
-                updated_at = NOW()
             "#,
         )
         .bind(media_type)
@@ -1186,19 +2891,7 @@ impl Database {
         .execute(&self.pool)
         .await?;
 
-        // Get the ID of the inserted/updated item
-        if result.rows_affected() > 0 {
-            let id: (i32,) = sqlx::query_as(
-                "SELECT id FROM global_watchlist WHERE media_type = ? AND title = ?",
-            )
-            .bind(media_type)
-            .bind(title)
-            .fetch_one(&self.pool)
-            .await?;
-            Ok(id.0 as u64)
-        } else {
-            Err(anyhow::anyhow!("Failed to add to global watchlist"))
-        }
+        Ok(result.last_insert_id())
     }
 
     pub async fn vote_global_watchlist(
@@ -1206,7 +2899,7 @@ impl Database {
         watchlist_id: u64,
         user_id: u64,
         vote_type: &str,
-    ) -> Result<()> {
+    ) -> DbResult<()> {
         sqlx::query(
             r#"
             INSERT INTO global_watchlist_votes (watchlist_id, user_id, vote_type)
@@ -1229,7 +2922,7 @@ impl Database {
         &self,
         watchlist_id: u64,
         user_id: u64,
-    ) -> Result<bool> {
+    ) -> DbResult<bool> {
         let result = sqlx::query(
             "DELETE FROM global_watchlist_votes WHERE watchlist_id = ? AND user_id = ?",
         )
@@ -1241,22 +2934,14 @@ impl Database {
         Ok(result.rows_affected() > 0)
     }
 
+    /// Global watchlist items ranked by net votes. Items marked watched are left out
+    /// unless `include_completed` is set.
     pub async fn get_global_watchlist(
         &self,
         limit: u32,
         media_type: Option<&str>,
-    ) -> Result<
-        Vec<(
-            i32,
-            String,
-            String,
-            Option<String>,
-            Option<String>,
-            i64,
-            i64,
-            String,
-        )>,
-    > {
+        include_completed: bool,
+    ) -> Result<Vec<GlobalWatchlistItem>> {
         let query = if let Some(media_type) = media_type {
             sqlx::query_as(
                 r#"
@@ -1268,12 +2953,15 @@ impl Database {
                     gw.description,
                     CAST(COALESCE(SUM(CASE WHEN gwv.vote_type = 'up' THEN 1 ELSE 0 END), 0) AS SIGNED) as upvotes,
                     CAST(COALESCE(SUM(CASE WHEN gwv.vote_type = 'down' THEN 1 ELSE 0 END), 0) AS SIGNED) as downvotes,
-                    u.username as added_by_username
+                    u.username as added_by_username,
+                    gw.added_by,
+                    gw.added_at,
+                    gw.completed_at
                 FROM global_watchlist gw
                 LEFT JOIN global_watchlist_votes gwv ON gw.id = gwv.watchlist_id
                 JOIN users u ON gw.added_by = u.discord_user_id
-                WHERE gw.media_type = ?
-                GROUP BY gw.id, gw.media_type, gw.title, gw.url, gw.description, u.username
+                WHERE gw.media_type = ? AND (? OR gw.completed_at IS NULL)
+                GROUP BY gw.id, gw.media_type, gw.title, gw.url, gw.description, u.username, gw.added_by, gw.added_at, gw.completed_at
                 ORDER BY (CAST(COALESCE(SUM(CASE WHEN gwv.vote_type = 'up' THEN 1 ELSE 0 END), 0) AS SIGNED) - 
                      CAST(COALESCE(SUM(CASE WHEN gwv.vote_type = 'down' THEN 1 ELSE 0 END), 0) AS SIGNED)) DESC, 
                      gw.added_at DESC
@@ -1281,6 +2969,7 @@ impl Database {
                 "#,
             )
             .bind(media_type)
+            .bind(include_completed)
             .bind(limit)
         } else {
             sqlx::query_as(
@@ -1293,34 +2982,104 @@ impl Database {
                     gw.description,
                     CAST(COALESCE(SUM(CASE WHEN gwv.vote_type = 'up' THEN 1 ELSE 0 END), 0) AS SIGNED) as upvotes,
                     CAST(COALESCE(SUM(CASE WHEN gwv.vote_type = 'down' THEN 1 ELSE 0 END), 0) AS SIGNED) as downvotes,
-                    u.username as added_by_username
+                    u.username as added_by_username,
+                    gw.added_by,
+                    gw.added_at,
+                    gw.completed_at
                 FROM global_watchlist gw
                 LEFT JOIN global_watchlist_votes gwv ON gw.id = gwv.watchlist_id
                 JOIN users u ON gw.added_by = u.discord_user_id
-                GROUP BY gw.id, gw.media_type, gw.title, gw.url, gw.description, u.username
+                WHERE ? OR gw.completed_at IS NULL
+                GROUP BY gw.id, gw.media_type, gw.title, gw.url, gw.description, u.username, gw.added_by, gw.added_at, gw.completed_at
                 ORDER BY (CAST(COALESCE(SUM(CASE WHEN gwv.vote_type = 'up' THEN 1 ELSE 0 END), 0) AS SIGNED) - 
                      CAST(COALESCE(SUM(CASE WHEN gwv.vote_type = 'down' THEN 1 ELSE 0 END), 0) AS SIGNED)) DESC, 
                      gw.added_at DESC
                 LIMIT ?
                 "#,
             )
+            .bind(include_completed)
             .bind(limit)
         };
 
-        let items: Vec<(
-            i32,
-            String,
-            String,
-            Option<String>,
-            Option<String>,
-            i64,
-            i64,
-            String,
-        )> = query.fetch_all(&self.pool).await?;
+        let items: Vec<GlobalWatchlistItem> = query.fetch_all(&self.pool).await?;
 
         Ok(items)
     }
 
+    /// A single global watchlist item with its vote counts, in the same shape as
+    /// `get_global_watchlist`.
+    pub async fn get_global_watchlist_item(
+        &self,
+        watchlist_id: u64,
+    ) -> Result<Option<GlobalWatchlistItem>> {
+        let item = sqlx::query_as(
+            r#"
+            SELECT 
+                gw.id,
+                gw.media_type,
+                gw.title,
+                gw.url,
+                gw.description,
+                CAST(COALESCE(SUM(CASE WHEN gwv.vote_type = 'up' THEN 1 ELSE 0 END), 0) AS SIGNED) as upvotes,
+                CAST(COALESCE(SUM(CASE WHEN gwv.vote_type = 'down' THEN 1 ELSE 0 END), 0) AS SIGNED) as downvotes,
+                u.username as added_by_username,
+                gw.added_by,
+                gw.added_at,
+                gw.completed_at
+            FROM global_watchlist gw
+            LEFT JOIN global_watchlist_votes gwv ON gw.id = gwv.watchlist_id
+            JOIN users u ON gw.added_by = u.discord_user_id
+            WHERE gw.id = ?
+            GROUP BY gw.id, gw.media_type, gw.title, gw.url, gw.description, u.username, gw.added_by, gw.added_at, gw.completed_at
+            "#,
+        )
+        .bind(watchlist_id as i64)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(item)
+    }
+
+    /// Marks a global watchlist item as watched by the community, or clears the mark.
+    /// Re-marking keeps the original completion time.
+    pub async fn set_global_watchlist_completed(
+        &self,
+        watchlist_id: u64,
+        completed: bool,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE global_watchlist
+            SET completed_at = IF(?, COALESCE(completed_at, NOW()), NULL)
+            WHERE id = ?
+            "#,
+        )
+        .bind(completed)
+        .bind(watchlist_id as i64)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Sets the one-time "reached the vote threshold" flag on a global watchlist item.
+    /// Returns false if the flag already had that value, so only one caller announces an item.
+    pub async fn set_global_watchlist_promoted(
+        &self,
+        watchlist_id: u64,
+        promoted: bool,
+    ) -> Result<bool> {
+        let result =
+            sqlx::query("UPDATE global_watchlist SET promoted = ? WHERE id = ? AND promoted <> ?")
+                .bind(promoted)
+                .bind(watchlist_id as i64)
+                .bind(promoted)
+                .execute(&self.pool)
+                .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
     pub async fn get_user_vote_on_global_item(
         &self,
         watchlist_id: u64,
@@ -1341,21 +3100,10 @@ impl Database {
         &self,
         query: &str,
         limit: u32,
-    ) -> Result<
-        Vec<(
-            i32,
-            String,
-            String,
-            Option<String>,
-            Option<String>,
-            i64,
-            i64,
-            String,
-        )>,
-    > {
+    ) -> Result<Vec<GlobalWatchlistItem>> {
         let search_pattern = format!("%{}%", query);
 
-        let items: Vec<(i32, String, String, Option<String>, Option<String>, i64, i64, String)> = sqlx::query_as(
+        let items: Vec<GlobalWatchlistItem> = sqlx::query_as(
             r#"
             SELECT 
                 gw.id,
@@ -1365,12 +3113,15 @@ impl Database {
                 gw.description,
                 CAST(COALESCE(SUM(CASE WHEN gwv.vote_type = 'up' THEN 1 ELSE 0 END), 0) AS SIGNED) as upvotes,
                 CAST(COALESCE(SUM(CASE WHEN gwv.vote_type = 'down' THEN 1 ELSE 0 END), 0) AS SIGNED) as downvotes,
-                u.username as added_by_username
+                u.username as added_by_username,
+                gw.added_by,
+                gw.added_at,
+                gw.completed_at
             FROM global_watchlist gw
             LEFT JOIN global_watchlist_votes gwv ON gw.id = gwv.watchlist_id
             JOIN users u ON gw.added_by = u.discord_user_id
             WHERE gw.title LIKE ? OR gw.description LIKE ?
-            GROUP BY gw.id, gw.media_type, gw.title, gw.url, gw.description, u.username
+            GROUP BY gw.id, gw.media_type, gw.title, gw.url, gw.description, u.username, gw.added_by, gw.added_at, gw.completed_at
             ORDER BY (CAST(COALESCE(SUM(CASE WHEN gwv.vote_type = 'up' THEN 1 ELSE 0 END), 0) AS SIGNED) - 
                      CAST(COALESCE(SUM(CASE WHEN gwv.vote_type = 'down' THEN 1 ELSE 0 END), 0) AS SIGNED)) DESC, 
                      gw.added_at DESC
@@ -1594,24 +3345,26 @@ impl Database {
         Ok(result.rows_affected())
     }
 
-    pub async fn get_last_snort_meme(&self) -> Result<Option<String>> {
-        let result: Option<(String,)> = sqlx::query_as(
-            "SELECT setting_value FROM system_settings WHERE setting_key = 'last_snort_meme'",
-        )
-        .fetch_optional(&self.pool)
-        .await?;
+    /// The meme `/snort` last posted in a guild, as `giphy:{id}` or `local:{path}`.
+    pub async fn get_last_snort_meme(&self, guild_id: u64) -> Result<Option<String>> {
+        let result: Option<(String,)> =
+            sqlx::query_as("SELECT setting_value FROM system_settings WHERE setting_key = ?")
+                .bind(format!("last_snort_meme_{}", guild_id))
+                .fetch_optional(&self.pool)
+                .await?;
 
         Ok(result.map(|(value,)| value))
     }
 
-    pub async fn set_last_snort_meme(&self, meme_id: &str) -> Result<()> {
+    pub async fn set_last_snort_meme(&self, guild_id: u64, meme_id: &str) -> Result<()> {
         sqlx::query(
             r#"
             INSERT INTO system_settings (setting_key, setting_value)
-            VALUES ('last_snort_meme', ?)
+            VALUES (?, ?)
             ON DUPLICATE KEY UPDATE setting_value = VALUES(setting_value)
             "#,
         )
+        .bind(format!("last_snort_meme_{}", guild_id))
         .bind(meme_id)
         .execute(&self.pool)
         .await?;
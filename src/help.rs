@@ -0,0 +1,457 @@
+/// Category a command is listed under in `/help`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HelpCategory {
+    Moderation,
+    Media,
+    Fun,
+    Admin,
+}
+
+impl HelpCategory {
+    pub const ALL: [HelpCategory; 4] = [
+        HelpCategory::Moderation,
+        HelpCategory::Media,
+        HelpCategory::Fun,
+        HelpCategory::Admin,
+    ];
+
+    /// Heading for the category in the given Discord locale.
+    pub fn label(self, locale: &str) -> String {
+        let key = match self {
+            HelpCategory::Moderation => "help.categories.moderation",
+            HelpCategory::Media => "help.categories.media",
+            HelpCategory::Fun => "help.categories.fun",
+            HelpCategory::Admin => "help.categories.admin",
+        };
+        crate::i18n::t(key, locale)
+    }
+}
+
+/// A single slash command as described by `/help`.
+pub struct HelpEntry {
+    pub name: &'static str,
+    pub category: HelpCategory,
+    pub usage: &'static str,
+    pub summary: &'static str,
+    pub options: &'static str,
+    pub examples: &'static str,
+    pub access: &'static str,
+    /// Hidden from anyone who is not a super user.
+    pub super_user_only: bool,
+}
+
+pub const HELP_ENTRIES: &[HelpEntry] = &[
+    HelpEntry {
+        name: "kick",
+        category: HelpCategory::Moderation,
+        usage: "/kick <user> [reason] [guild]",
+        summary: "Kick a user from this server or a specific guild",
+        options: "`user` - username, @handle, nickname or user ID\n\
+                  `reason` - shown in the audit log\n\
+                  `guild` - act on another guild instead of this one",
+        examples: "`/kick user:spammer reason:Advertising`",
+        access: "Whitelisted only",
+        super_user_only: false,
+    },
+    HelpEntry {
+        name: "ban",
+        category: HelpCategory::Moderation,
+        usage: "/ban <user> [reason] [delete_days] [guild]",
+        summary: "Ban a user from this server or a specific guild",
+        options: "`user` - username, @handle, nickname or user ID\n\
+                  `reason` - shown in the audit log\n\
+                  `delete_days` - delete 0-7 days of their messages\n\
+                  `guild` - act on another guild instead of this one",
+        examples:
+            "`/ban user:raider delete_days:1`\n`/ban user:123456789012345678 reason:Alt account`",
+        access: "Whitelisted only",
+        super_user_only: false,
+    },
+    HelpEntry {
+        name: "timeout",
+        category: HelpCategory::Moderation,
+        usage: "/timeout <user> <duration> [reason] [guild]",
+        summary: "Timeout a user in this server or a specific guild",
+        options: "`user` - username, @handle, nickname or user ID\n\
+                  `duration` - minutes, 1-40320 (28 days)\n\
+                  `reason` - shown in the audit log\n\
+                  `guild` - act on another guild instead of this one",
+        examples: "`/timeout user:loudmouth duration:60 reason:Cool off`",
+        access: "Whitelisted only",
+        super_user_only: false,
+    },
+    HelpEntry {
+        name: "untimeout",
+        category: HelpCategory::Moderation,
+        usage: "/untimeout <user> [reason] [guild]",
+        summary: "Lift an active timeout early",
+        options: "`user` - username, @handle, nickname or user ID\n\
+                  `reason` - shown in the audit log\n\
+                  `guild` - act on another guild instead of this one",
+        examples: "`/untimeout user:loudmouth reason:Appeal accepted`",
+        access: "Whitelisted only",
+        super_user_only: false,
+    },
+    HelpEntry {
+        name: "massmute",
+        category: HelpCategory::Moderation,
+        usage: "/massmute <duration> <users> [reason] [guild]",
+        summary: "Timeout many users at once, e.g. during a raid",
+        options: "`duration` - minutes, 1-40320 (28 days)\n\
+                  `users` - up to 25 usernames, @handles or user IDs separated by commas or spaces\n\
+                  `reason` - shown in the audit log\n\
+                  `guild` - act on another guild instead of this one",
+        examples: "`/massmute duration:60 users:raider1, raider2, 123456789012345678`",
+        access: "Whitelisted only",
+        super_user_only: false,
+    },
+    HelpEntry {
+        name: "softban",
+        category: HelpCategory::Moderation,
+        usage: "/softban <user> [delete_days] [reason] [guild]",
+        summary: "Ban and unban a user to purge their recent messages",
+        options: "`user` - username, @handle, nickname or user ID\n\
+                  `delete_days` - delete 1-7 days of their messages\n\
+                  `reason` - shown in the audit log\n\
+                  `guild` - act on another guild instead of this one",
+        examples: "`/softban user:spammer delete_days:7`",
+        access: "Whitelisted only",
+        super_user_only: false,
+    },
+    HelpEntry {
+        name: "purge",
+        category: HelpCategory::Moderation,
+        usage: "/purge <count>",
+        summary: "Bulk-delete recent messages in this channel",
+        options: "`count` - 1-100 messages; messages older than 14 days are skipped",
+        examples: "`/purge count:25`",
+        access: "Whitelisted only",
+        super_user_only: false,
+    },
+    HelpEntry {
+        name: "userinfo",
+        category: HelpCategory::Moderation,
+        usage: "/userinfo <user>",
+        summary: "Show tracked profile, status and nickname history for a user",
+        options: "`user` - username, @handle, nickname or user ID",
+        examples: "`/userinfo user:someone`",
+        access: "Whitelisted only",
+        super_user_only: false,
+    },
+    HelpEntry {
+        name: "search",
+        category: HelpCategory::Moderation,
+        usage: "/search users <query>",
+        summary: "Find tracked users by partial username, handle or nickname",
+        options: "`query` - part of a username, @handle or server nickname. Shows up to 25 matches \
+                  with their user IDs for ID-based moderation",
+        examples: "`/search users query:raid`",
+        access: "Whitelisted only",
+        super_user_only: false,
+    },
+    HelpEntry {
+        name: "churn",
+        category: HelpCategory::Moderation,
+        usage: "/churn [days]",
+        summary: "Show member joins, leaves and retention for this server",
+        options: "`days` - how far back to look, 1-365 (default 30)",
+        examples: "`/churn`\n`/churn days:90`",
+        access: "Whitelisted only",
+        super_user_only: false,
+    },
+    HelpEntry {
+        name: "serverinfo",
+        category: HelpCategory::Moderation,
+        usage: "/serverinfo",
+        summary: "Overview of this server: members, channels, roles, boosts and logged activity",
+        options: "None",
+        examples: "`/serverinfo`",
+        access: "Whitelisted only",
+        super_user_only: false,
+    },
+    HelpEntry {
+        name: "thread",
+        category: HelpCategory::Moderation,
+        usage: "/thread stats [days]",
+        summary: "Show who starts the most threads and where",
+        options: "`stats [days]` - top thread starters and channels, 1-365 days (default 7)",
+        examples: "`/thread stats`\n`/thread stats days:30`",
+        access: "Whitelisted only",
+        super_user_only: false,
+    },
+    HelpEntry {
+        name: "watchlist",
+        category: HelpCategory::Media,
+        usage: "/watchlist <view|add|remove|priority|remind|search|export|import|clear>",
+        summary: "Manage your media watchlist and view recommendations",
+        options: "`view [type] [media_type] [min_mentions] [sort]` - your list or top recommendations\n\
+                  `add <type> <title> [url] [priority]` - add an item (`other` items with a known link are refiled, e.g. YouTube or Steam)\n\
+                  `remove <type> <title>` - remove an item\n\
+                  `priority <type> <title> <new_priority>` - change priority (1-100)\n\
+                  `remind <type> <title> <when>` - DM reminder (e.g. 2h, 3d, 1w; up to a year ahead)\n\
+                  `search <query>` - search community mentions\n\
+                  `export <data> <format>` - CSV, JSON, Markdown, TOML or YAML\n\
+                  `import <file>` - add items from an exported CSV\n\
+                  `clear [type] [status]` - remove all matching items (asks to confirm)",
+        examples: "`/watchlist add type:anime title:Frieren priority:90`\n\
+                   `/watchlist view type:top media_type:movie`",
+        access: "Everyone",
+        super_user_only: false,
+    },
+    HelpEntry {
+        name: "global",
+        category: HelpCategory::Media,
+        usage: "/global <view|add|vote|search|watched>",
+        summary: "Browse and vote on the community watchlist",
+        options: "`view [type] [show_completed]` - list items sorted by net votes, with 👍/👎 vote buttons; watched items are hidden by default\n\
+                  `add <type> <title> [url] [description]` - add an item (auto-upvoted)\n\
+                  `vote <item> <vote>` - upvote, downvote or remove your vote\n\
+                  `search <query>` - search titles and descriptions\n\
+                  `watched <item> [undo]` - mark an item as watched by the community (super users)",
+        examples: "`/global add type:game title:Hades`\n`/global vote item:Hades vote:upvote`",
+        access: "Everyone",
+        super_user_only: false,
+    },
+    HelpEntry {
+        name: "recommendations",
+        category: HelpCategory::Media,
+        usage: "/recommendations by <user> [days]",
+        summary: "See what one member has recommended most",
+        options: "`by <user> [days]` - titles the member mentioned, ranked by how often (default 30 days). \
+                  Looking up someone else can be limited to whitelisted users with the \
+                  `public_user_recommendations` setting",
+        examples: "`/recommendations by user:alice`\n`/recommendations by user:alice days:90`",
+        access: "Everyone",
+        super_user_only: false,
+    },
+    HelpEntry {
+        name: "privacy",
+        category: HelpCategory::Media,
+        usage: "/privacy <optout|optin|status|delete>",
+        summary: "Control what the bot keeps about you",
+        options: "`optout` - stop scanning your messages for media titles and links\n\
+                  `optin` - count your messages again\n\
+                  `status` - check whether you're opted out\n\
+                  `delete` - permanently erase your logged messages, recommendations, watchlist and votes \
+                  after a confirmation button",
+        examples: "`/privacy optout`\n`/privacy status`\n`/privacy delete`",
+        access: "Everyone",
+        super_user_only: false,
+    },
+    HelpEntry {
+        name: "gif",
+        category: HelpCategory::Media,
+        usage: "/gif [search]",
+        summary: "Post a random GIF from the GIPHY cache",
+        options: "`search` - only pick from GIFs cached for this search term",
+        examples: "`/gif`\n`/gif search:destiny`",
+        access: "Everyone",
+        super_user_only: false,
+    },
+    HelpEntry {
+        name: "meme",
+        category: HelpCategory::Media,
+        usage: "/meme <random|list|browse|delete> [folder]",
+        summary: "Post or browse saved memes",
+        options: "`random [folder]` - post a random meme, optionally from one folder\n\
+                  `list [folder]` - list folders with file counts, or one folder's files\n\
+                  `browse [folder]` - page through a folder with Previous/Next/Random buttons\n\
+                  `delete <folder> <file>` - delete a saved meme (super users; also a Delete button in `browse`)",
+        examples: "`/meme random`\n`/meme list folder:reactions`\n`/meme browse folder:reactions`\n`/meme delete folder:reactions file:abc.png`",
+        access: "Everyone",
+        super_user_only: false,
+    },
+    HelpEntry {
+        name: "snort",
+        category: HelpCategory::Fun,
+        usage: "/snort [action]",
+        summary: "Snort some brightdust!",
+        options: "`action:reset` - reset the global counter to zero after confirming (super users only)",
+        examples: "`/snort`\n`/snort action:reset`",
+        access: "Everyone",
+        super_user_only: false,
+    },
+    HelpEntry {
+        name: "voicetime",
+        category: HelpCategory::Fun,
+        usage: "/voicetime [days]",
+        summary: "Show the top 10 members by time spent in voice",
+        options: "`days` - how far back to look (default 7)",
+        examples: "`/voicetime`\n`/voicetime days:30`",
+        access: "Everyone",
+        super_user_only: false,
+    },
+    HelpEntry {
+        name: "voiceactivity",
+        category: HelpCategory::Fun,
+        usage: "/voiceactivity [channel] [days]",
+        summary: "Show which voice channels are actually used",
+        options: "`channel` - only show one voice or stage channel\n\
+                  `days` - how far back to look (default 7)",
+        examples: "`/voiceactivity`\n`/voiceactivity channel:#gaming days:30`",
+        access: "Everyone",
+        super_user_only: false,
+    },
+    HelpEntry {
+        name: "events",
+        category: HelpCategory::Fun,
+        usage: "/events upcoming",
+        summary: "List upcoming scheduled events with interest counts",
+        options: "`upcoming` - events that have not started yet",
+        examples: "`/events upcoming`",
+        access: "Everyone",
+        super_user_only: false,
+    },
+    HelpEntry {
+        name: "uptime",
+        category: HelpCategory::Admin,
+        usage: "/uptime",
+        summary: "Show how long the bot has been running",
+        options: "None",
+        examples: "`/uptime`",
+        access: "Everyone",
+        super_user_only: false,
+    },
+    HelpEntry {
+        name: "cache",
+        category: HelpCategory::Admin,
+        usage: "/cache [on|off|status]",
+        summary: "Toggle or check media caching",
+        options: "`action` - `on`, `off` or `status` (default)",
+        examples: "`/cache action:off`",
+        access: "Whitelisted only",
+        super_user_only: false,
+    },
+    HelpEntry {
+        name: "health",
+        category: HelpCategory::Admin,
+        usage: "/health",
+        summary: "Check database, connection pool, cache and uptime",
+        options: "None",
+        examples: "`/health`",
+        access: "Super users only",
+        super_user_only: true,
+    },
+    HelpEntry {
+        name: "scan",
+        category: HelpCategory::Admin,
+        usage: "/scan <media|reset>",
+        summary: "Run or rewind the media recommendation scan",
+        options: "`media [limit]` - scan up to `limit` unscanned messages now (default 1000, max 50000)\n\
+                  `reset [purge_old]` - rewind the checkpoint so everything is rescanned, \
+                  optionally deleting recommendations from older detector versions first",
+        examples: "`/scan media limit:10000`\n`/scan reset purge_old:true`",
+        access: "Super users only",
+        super_user_only: true,
+    },
+    HelpEntry {
+        name: "digest",
+        category: HelpCategory::Admin,
+        usage: "/digest <channel|on|off|status>",
+        summary: "Configure the weekly top-recommendations digest",
+        options: "`channel <channel>` - where the digest is posted\n\
+                  `on` / `off` - start or stop posting it\n\
+                  `status` - show the channel, last post and next post",
+        examples: "`/digest channel channel:#recommendations`\n`/digest on`",
+        access: "Super users only",
+        super_user_only: true,
+    },
+    HelpEntry {
+        name: "config",
+        category: HelpCategory::Admin,
+        usage: "/config <list|get|set>",
+        summary: "View and change bot settings",
+        options: "`list` - every stored setting, plus known settings still on their defaults\n\
+                  `get <key>` - one setting and what it does\n\
+                  `set <key> <value>` - change a known setting; unknown keys are rejected",
+        examples: "`/config list`\n`/config set key:snort_cooldown_seconds value:60`",
+        access: "Super users only",
+        super_user_only: true,
+    },
+    HelpEntry {
+        name: "mediaconfig",
+        category: HelpCategory::Admin,
+        usage: "/mediaconfig <confidence|blacklist>",
+        summary: "Tune media recommendation detection",
+        options: "`confidence [type] [multiplier]` - scale detected confidence for anime, \
+                  tv_show, game or youtube. 1 leaves scores unchanged, 0 stops recording \
+                  that type. Without a multiplier, shows the current values\n\
+                  `blacklist add <term> [purge_existing]` - never record this title (whole \
+                  title, ignoring case); optionally delete rows already recorded with it\n\
+                  `blacklist remove <term>` / `blacklist list` - manage the blacklist",
+        examples: "`/mediaconfig confidence type:youtube multiplier:0.5`\n\
+                   `/mediaconfig blacklist add term:the best purge_existing:true`",
+        access: "Super users only",
+        super_user_only: true,
+    },
+    HelpEntry {
+        name: "alertchannel",
+        category: HelpCategory::Admin,
+        usage: "/alertchannel [channel] [clear]",
+        summary: "Choose where moderator alerts from every server are sent",
+        options: "`channel` - send all mod alerts here; leave empty to see the current channel\n\
+                  `clear` - go back to each server's system channel",
+        examples: "`/alertchannel channel:#mod-alerts`\n`/alertchannel clear:true`",
+        access: "Super users only",
+        super_user_only: true,
+    },
+    HelpEntry {
+        name: "keywords",
+        category: HelpCategory::Admin,
+        usage: "/keywords <add|remove|list>",
+        summary: "Alert moderators when watched words are posted in this server",
+        options: "`add <keyword> [severity]` - watch for a whole word or phrase, ignoring case. \
+                  `*` matches any letters, so `*scam*` also catches \"scammer\". Severity is \
+                  low, medium (default) or high and sets the alert colour\n\
+                  `remove <keyword>` - stop watching for a keyword\n\
+                  `list` - show this server's watched keywords",
+        examples: "`/keywords add keyword:free nitro severity:high`\n`/keywords add keyword:*scam*`",
+        access: "Super users only",
+        super_user_only: true,
+    },
+    HelpEntry {
+        name: "reactions",
+        category: HelpCategory::Admin,
+        usage: "/reactions <add|remove|list>",
+        summary: "React with an emoji when a keyword is mentioned in this server",
+        options: "`add <keyword> <emoji>` - react to messages containing the keyword as a whole \
+                  word or phrase, ignoring case. Setting a keyword again replaces its emoji\n\
+                  `remove <keyword>` - stop reacting to a keyword\n\
+                  `list` - show this server's keyword reactions",
+        examples: "`/reactions add keyword:brightdust emoji:✨`\n`/reactions remove keyword:brightdust`",
+        access: "Super users only",
+        super_user_only: true,
+    },
+    HelpEntry {
+        name: "welcome",
+        category: HelpCategory::Admin,
+        usage: "/welcome <channel|template|test|clear>",
+        summary: "Configure the message posted when members join this server",
+        options: "`channel <channel>` - where welcome messages go in this server\n\
+                  `template <message> [global]` - message text; `{user}` and `{guild}` are \
+                  replaced, `\\n` starts a new line. `global` saves it as the default for every server\n\
+                  `test` - preview the message as if you just joined\n\
+                  `clear` - remove this server's channel and message",
+        examples: "`/welcome channel channel:#welcome`\n\
+                   `/welcome template message:Welcome to {guild}, {user}!`",
+        access: "Super users only",
+        super_user_only: true,
+    },
+    HelpEntry {
+        name: "whitelist",
+        category: HelpCategory::Admin,
+        usage: "/whitelist <add|remove|list|superadd|superremove> [user]",
+        summary: "Manage the command whitelist and super users",
+        options: "`action` - what to do\n\
+                  `user` - username, @handle, nickname or user ID (not needed for `list`)",
+        examples: "`/whitelist action:add user:newmod`\n`/whitelist action:list`",
+        access: "Super users only",
+        super_user_only: true,
+    },
+];
+
+/// Looks up a command by name, tolerating a leading `/`.
+pub fn find(name: &str) -> Option<&'static HelpEntry> {
+    let name = name.trim().trim_start_matches('/').to_lowercase();
+    HELP_ENTRIES.iter().find(|entry| entry.name == name)
+}
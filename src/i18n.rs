@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use tracing::{error, warn};
+
+/// Locale every lookup falls back to; its file must define every key.
+pub const DEFAULT_LOCALE: &str = "en-US";
+
+/// String tables compiled into the binary, keyed by Discord locale code.
+const LOCALES: &[(&str, &str)] = &[
+    ("en-US", include_str!("../locales/en-US.toml")),
+    ("es-ES", include_str!("../locales/es-ES.toml")),
+];
+
+type Catalogue = HashMap<String, String>;
+
+fn catalogues() -> &'static HashMap<&'static str, Catalogue> {
+    static CATALOGUES: OnceLock<HashMap<&'static str, Catalogue>> = OnceLock::new();
+    CATALOGUES.get_or_init(|| {
+        LOCALES
+            .iter()
+            .map(|(locale, source)| {
+                let mut strings = Catalogue::new();
+                match source.parse::<toml::Table>() {
+                    Ok(table) => flatten("", &table, &mut strings),
+                    Err(e) => error!("Failed to parse locale {}: {}", locale, e),
+                }
+                (*locale, strings)
+            })
+            .collect()
+    })
+}
+
+// Nested tables become dotted keys, e.g. `[help] title` -> `help.title`
+fn flatten(prefix: &str, table: &toml::Table, out: &mut Catalogue) {
+    for (key, value) in table {
+        let key = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        match value {
+            toml::Value::String(text) => {
+                out.insert(key, text.clone());
+            }
+            toml::Value::Table(nested) => flatten(&key, nested, out),
+            _ => warn!("Ignoring non-string locale entry {}", key),
+        }
+    }
+}
+
+/// Picks the catalogue for a Discord locale such as `es-ES` or `es-419`: an exact match
+/// first, then any catalogue for the same language, then English.
+fn resolve(locale: &str) -> &'static str {
+    let available = catalogues();
+    if let Some((code, _)) = available.get_key_value(locale) {
+        return code;
+    }
+
+    let language = locale.split('-').next().unwrap_or(locale);
+    LOCALES
+        .iter()
+        .map(|(code, _)| *code)
+        .find(|code| code.split('-').next() == Some(language))
+        .unwrap_or(DEFAULT_LOCALE)
+}
+
+/// Looks up `key` for `locale`, falling back to English and then to the key itself.
+pub fn t(key: &str, locale: &str) -> String {
+    let available = catalogues();
+    available
+        .get(resolve(locale))
+        .and_then(|strings| strings.get(key))
+        .or_else(|| available.get(DEFAULT_LOCALE)?.get(key))
+        .cloned()
+        .unwrap_or_else(|| {
+            warn!("Missing translation for {}", key);
+            key.to_string()
+        })
+}
+
+/// Like [`t`], then replaces each `{name}` placeholder with its value.
+pub fn t_with(key: &str, locale: &str, args: &[(&str, &str)]) -> String {
+    args.iter().fold(t(key, locale), |text, (name, value)| {
+        text.replace(&format!("{{{}}}", name), value)
+    })
+}
@@ -1,12 +1,58 @@
-use crate::db::Database;
+use crate::db::{Database, UserRecord};
 use crate::media::MediaCache;
-use crate::media_detector::MediaDetector;
+use crate::media_detector::{
+    remove_blacklisted, top_recommendation_field, ConfidenceTuning, MediaDetector, DETECTOR_VERSION,
+};
+use crate::settings::Settings;
+use crate::steam;
 use anyhow::Result;
-use serenity::all::Context;
+use chrono::{DateTime, Utc};
+use serenity::all::{
+    ChannelId, Colour, Context, CreateEmbed, CreateMessage, GuildId, Http, UserId,
+};
+use std::collections::HashSet;
 use std::sync::Arc;
 use tokio_cron_scheduler::{Job, JobScheduler};
 use tracing::info;
 
+/// Setting held while a media recommendation scan runs, so manual and scheduled scans never overlap.
+const SCAN_IN_PROGRESS_KEY: &str = "scan_in_progress";
+
+/// A scan flag not refreshed for this long is assumed to be left over from a crash.
+const SCAN_FLAG_STALE_SECS: u64 = 30 * 60;
+
+/// Setting holding the ID of the channel the weekly digest is posted to.
+pub const DIGEST_CHANNEL_KEY: &str = "digest_channel";
+
+/// Setting that turns the weekly digest on ("true") or off.
+pub const DIGEST_ENABLED_KEY: &str = "digest_enabled";
+
+/// Setting holding when the digest was last posted (RFC 3339), so restarts don't repost it.
+pub const DIGEST_LAST_POSTED_KEY: &str = "digest_last_posted";
+
+/// Days between digests, and how far back each digest looks.
+pub const DIGEST_INTERVAL_DAYS: i64 = 7;
+
+/// Per-message meme save state kept in settings until a folder button is clicked.
+pub const MEME_SETTING_PREFIXES: [&str; 3] =
+    ["meme_buttons_", "meme_selected_", "meme_processing_"];
+
+/// Meme save state untouched for this many days is assumed abandoned.
+const MEME_SETTING_STALE_DAYS: i64 = 7;
+
+/// Button confirmations kept in settings until answered. Each is deleted by a timer task a
+/// minute after it is created, which a restart skips.
+pub const PENDING_CONFIRMATION_PREFIXES: [&str; 5] = [
+    "ban_pending_",
+    "watchlist_pending_",
+    "watchlist_clear_pending_",
+    "snort_reset_pending_",
+    "privacy_delete_pending_",
+];
+
+/// Confirmations older than this are long expired and only left over from a restart.
+const PENDING_CONFIRMATION_STALE_DAYS: i64 = 1;
+
 pub async fn start_background_jobs(
     ctx: Arc<Context>,
     db: Database,
@@ -101,6 +147,20 @@ pub async fn start_background_jobs(
 
     scheduler.add(status_cleanup_job).await?;
 
+    // Stale meme button and confirmation cleanup job - runs daily at 4:30 AM
+    let db_meme_cleanup = db.clone();
+
+    let meme_settings_cleanup_job = Job::new_async("0 30 4 * * *", move |_uuid, _l| {
+        let db = db_meme_cleanup.clone();
+        Box::pin(async move {
+            tokio::spawn(async move {
+                cleanup_stale_settings(db).await;
+            });
+        })
+    })?;
+
+    scheduler.add(meme_settings_cleanup_job).await?;
+
     // Media recommendations scanning job - runs every 30 minutes
     let db_media_scan = db.clone();
 
@@ -117,21 +177,94 @@ pub async fn start_background_jobs(
 
     scheduler.add(media_scan_job).await?;
 
-    // GIPHY cache cleanup job - runs daily at 5 AM
-    let db_giphy_cleanup = db.clone();
+    // GIPHY cache refresh job - runs every 6 hours, also cleans up stale entries
+    let db_giphy_refresh = db.clone();
 
-    let giphy_cleanup_job = Job::new_async("0 0 5 * * *", move |_uuid, _l| {
-        let db = db_giphy_cleanup.clone();
+    let giphy_refresh_job = Job::new_async("0 0 */6 * * *", move |_uuid, _l| {
+        let db = db_giphy_refresh.clone();
         Box::pin(async move {
             tokio::spawn(async move {
-                if let Err(e) = cleanup_old_giphy_cache(db).await {
+                if let Err(e) = cleanup_old_giphy_cache(db.clone()).await {
                     tracing::error!("Failed to cleanup old GIPHY cache: {}", e);
                 }
+                if let Err(e) = refresh_giphy_cache(db).await {
+                    tracing::error!("Failed to refresh GIPHY cache: {}", e);
+                }
+            });
+        })
+    })?;
+
+    scheduler.add(giphy_refresh_job).await?;
+
+    // Watchlist reminder job - runs every 5 minutes
+    let db_reminders = db.clone();
+    let ctx_reminders = ctx.clone();
+
+    let watchlist_reminder_job = Job::new_async("0 */5 * * * *", move |_uuid, _l| {
+        let db = db_reminders.clone();
+        let ctx = ctx_reminders.clone();
+        Box::pin(async move {
+            tokio::spawn(async move {
+                if let Err(e) = send_watchlist_reminders(ctx, db).await {
+                    tracing::error!("Failed to send watchlist reminders: {}", e);
+                }
+            });
+        })
+    })?;
+
+    scheduler.add(watchlist_reminder_job).await?;
+
+    // Scheduled event reminder job - runs every 5 minutes
+    let db_event_reminders = db.clone();
+    let ctx_event_reminders = ctx.clone();
+
+    let event_reminder_job = Job::new_async("0 */5 * * * *", move |_uuid, _l| {
+        let db = db_event_reminders.clone();
+        let ctx = ctx_event_reminders.clone();
+        Box::pin(async move {
+            tokio::spawn(async move {
+                if let Err(e) = send_event_reminders(ctx, db).await {
+                    tracing::error!("Failed to send event reminders: {}", e);
+                }
             });
         })
     })?;
 
-    scheduler.add(giphy_cleanup_job).await?;
+    scheduler.add(event_reminder_job).await?;
+
+    // Database health check job - runs every 5 minutes
+    let db_health = db.clone();
+
+    let health_check_job = Job::new_async("0 */5 * * * *", move |_uuid, _l| {
+        let db = db_health.clone();
+        Box::pin(async move {
+            tokio::spawn(async move {
+                if let Err(e) = db.ping().await {
+                    tracing::warn!("Database health check failed: {}", e);
+                }
+            });
+        })
+    })?;
+
+    scheduler.add(health_check_job).await?;
+
+    // Weekly digest job - checks hourly, posts once the last digest is a week old
+    let db_digest = db.clone();
+    let ctx_digest = ctx.clone();
+
+    let digest_job = Job::new_async("0 0 * * * *", move |_uuid, _l| {
+        let db = db_digest.clone();
+        let ctx = ctx_digest.clone();
+        Box::pin(async move {
+            tokio::spawn(async move {
+                if let Err(e) = post_weekly_digest(ctx, db).await {
+                    tracing::error!("Failed to post weekly digest: {}", e);
+                }
+            });
+        })
+    })?;
+
+    scheduler.add(digest_job).await?;
 
     scheduler.start().await?;
 
@@ -218,15 +351,44 @@ async fn sync_all_users(ctx: Arc<Context>, db: Database) -> Result<()> {
     Ok(())
 }
 
+/// Fetches a guild's full member list from the API page by page and upserts each
+/// page, for guilds whose gateway member list is incomplete. Returns members synced.
+pub async fn sync_guild_members_from_api(
+    http: Arc<Http>,
+    db: Database,
+    guild_id: GuildId,
+) -> Result<usize> {
+    const PAGE_SIZE: u64 = 1000;
+
+    let mut after: Option<UserId> = None;
+    let mut synced = 0;
+
+    loop {
+        let members = guild_id.members(&http, Some(PAGE_SIZE), after).await?;
+
+        let users: Vec<UserRecord> = members.iter().map(UserRecord::from_member).collect();
+        match db.update_users_batch(users).await {
+            Ok(_) => synced += members.len(),
+            Err(e) => tracing::error!("Failed to update members of guild {}: {}", guild_id, e),
+        }
+
+        // Members come back ordered by user ID, so the last one is the next cursor
+        match members.last() {
+            Some(last) if members.len() as u64 == PAGE_SIZE => after = Some(last.user.id),
+            _ => break,
+        }
+    }
+
+    Ok(synced)
+}
+
 async fn cleanup_old_media(db: Database, media_cache: MediaCache) -> Result<()> {
     info!("Starting media cleanup job");
 
     // Check if media caching is enabled
-    if let Ok(Some(cache_enabled)) = db.get_setting("cache_media").await {
-        if cache_enabled != "true" {
-            info!("Media caching is disabled, skipping cleanup");
-            return Ok(());
-        }
+    if !Settings::new(&db).get_bool("cache_media").await {
+        info!("Media caching is disabled, skipping cleanup");
+        return Ok(());
     }
 
     // Delete files older than 31 days
@@ -245,6 +407,17 @@ async fn cleanup_old_media(db: Database, media_cache: MediaCache) -> Result<()>
         Err(e) => tracing::error!("Failed to query old cached media: {}", e),
     }
 
+    // Enforce the total cache size cap, evicting the oldest files first
+    let max_bytes = Settings::new(&db).get_u64("media_cache_max_bytes").await;
+
+    match media_cache.enforce_size_limit(&db, max_bytes).await {
+        Ok((total, freed)) => info!(
+            "Media cache size: {} bytes (limit {} bytes), freed {} bytes",
+            total, max_bytes, freed
+        ),
+        Err(e) => tracing::error!("Failed to enforce media cache size limit: {}", e),
+    }
+
     info!("Media cleanup job completed");
     Ok(())
 }
@@ -403,6 +576,7 @@ async fn scan_single_channel(
                     message.id.get(),
                     message.author.id.get(),
                     channel_id.get(),
+                    Some(guild_id.get()),
                     &message.content,
                     message.timestamp.to_utc(),
                 )
@@ -428,7 +602,7 @@ async fn scan_single_channel(
         }
 
         // Log progress
-        if total_messages % 1000 == 0 {
+        if total_messages.is_multiple_of(1000) {
             info!(
                 "Scanned {} messages so far from channel {}",
                 total_messages, channel_id
@@ -470,6 +644,12 @@ async fn check_expired_polls(db: Database) -> Result<()> {
     info!("Found {} expired polls to close", expired_polls.len());
 
     for (poll_id,) in expired_polls {
+        // Preserve the final tally before votes are eventually purged
+        if let Err(e) = db.snapshot_poll_results(&poll_id).await {
+            tracing::error!("Failed to snapshot results for poll {}: {}", poll_id, e);
+            continue;
+        }
+
         match db.close_poll(&poll_id).await {
             Ok(_) => info!("Closed expired poll: {}", poll_id),
             Err(e) => tracing::error!("Failed to close expired poll {}: {}", poll_id, e),
@@ -479,6 +659,26 @@ async fn check_expired_polls(db: Database) -> Result<()> {
     Ok(())
 }
 
+/// Removes meme button data for uploads nobody picked a folder for, and confirmations
+/// orphaned by a restart, which would otherwise stay in `system_settings` forever.
+async fn cleanup_stale_settings(db: Database) {
+    let prefixes = MEME_SETTING_PREFIXES
+        .iter()
+        .map(|prefix| (*prefix, MEME_SETTING_STALE_DAYS))
+        .chain(
+            PENDING_CONFIRMATION_PREFIXES
+                .iter()
+                .map(|prefix| (*prefix, PENDING_CONFIRMATION_STALE_DAYS)),
+        );
+    for (prefix, stale_days) in prefixes {
+        match db.delete_stale_settings(prefix, stale_days).await {
+            Ok(0) => {}
+            Ok(deleted) => info!("Deleted {} stale {}* settings", deleted, prefix),
+            Err(e) => tracing::error!("Failed to clean up {}* settings: {}", prefix, e),
+        }
+    }
+}
+
 async fn cleanup_old_status_logs(db: Database) -> Result<()> {
     info!("Starting Discord logs cleanup job");
 
@@ -510,6 +710,70 @@ async fn cleanup_old_status_logs(db: Database) -> Result<()> {
 }
 
 async fn scan_for_media_recommendations(db: Database) -> Result<()> {
+    if run_media_scan(&db, None).await?.is_none() {
+        info!("Media recommendations scan already in progress, skipping scheduled run");
+    }
+
+    Ok(())
+}
+
+/// Runs a media recommendation scan from the current checkpoint, stopping after
+/// `limit` messages when given. Returns `(messages_scanned, recommendations_found)`,
+/// or `None` if another scan already holds the `scan_in_progress` flag.
+pub async fn run_media_scan(db: &Database, limit: Option<u32>) -> Result<Option<(u32, u32)>> {
+    if !db
+        .try_acquire_setting_flag(SCAN_IN_PROGRESS_KEY, SCAN_FLAG_STALE_SECS)
+        .await?
+    {
+        return Ok(None);
+    }
+
+    let result = scan_media_batches(db, limit).await;
+
+    if let Err(e) = db.delete_setting(SCAN_IN_PROGRESS_KEY).await {
+        tracing::error!("Failed to clear {} flag: {}", SCAN_IN_PROGRESS_KEY, e);
+    }
+
+    result.map(Some)
+}
+
+/// Rewinds the media scan checkpoint so the next scan re-processes every message,
+/// first deleting recommendations from older detector versions if `purge_old` is set.
+/// Returns how many recommendations were purged, or `None` if a scan is running.
+pub async fn reset_media_scan(db: &Database, purge_old: bool) -> Result<Option<u64>> {
+    if !db
+        .try_acquire_setting_flag(SCAN_IN_PROGRESS_KEY, SCAN_FLAG_STALE_SECS)
+        .await?
+    {
+        return Ok(None);
+    }
+
+    let result = async {
+        let purged = if purge_old {
+            db.delete_outdated_recommendations(DETECTOR_VERSION).await?
+        } else {
+            0
+        };
+        db.reset_media_scan_checkpoint().await?;
+        Ok(purged)
+    }
+    .await;
+
+    if let Err(e) = db.delete_setting(SCAN_IN_PROGRESS_KEY).await {
+        tracing::error!("Failed to clear {} flag: {}", SCAN_IN_PROGRESS_KEY, e);
+    }
+
+    if let Ok(purged) = result {
+        info!(
+            "Media scan checkpoint reset (detector v{}), purged {} outdated recommendations",
+            DETECTOR_VERSION, purged
+        );
+    }
+
+    result.map(Some)
+}
+
+async fn scan_media_batches(db: &Database, limit: Option<u32>) -> Result<(u32, u32)> {
     info!("Starting media recommendations scan");
 
     // Get last scanned message ID
@@ -526,15 +790,28 @@ async fn scan_for_media_recommendations(db: Database) -> Result<()> {
     // Create media detector
     let detector = MediaDetector::new();
 
+    // Users who ran /privacy optout are skipped; if the list can't be read, don't scan at all
+    let opted_out = db.get_media_tracking_optouts().await?;
+    let tuning = ConfidenceTuning::load(db).await;
+    let blacklist: HashSet<String> = db.get_media_blacklist().await?.into_iter().collect();
+
     // Process messages in batches
     const BATCH_SIZE: u32 = 1000;
-    let mut messages_scanned = 0;
+    let mut messages_scanned: u32 = 0;
     let mut recommendations_found = 0;
     let mut current_last_id = last_scanned_id;
 
     loop {
         // Get next batch of unscanned messages
-        let messages = match db.get_unscanned_messages(current_last_id, BATCH_SIZE).await {
+        let batch_size = match limit {
+            Some(limit) => BATCH_SIZE.min(limit - messages_scanned),
+            None => BATCH_SIZE,
+        };
+        if batch_size == 0 {
+            break;
+        }
+
+        let messages = match db.get_unscanned_messages(current_last_id, batch_size).await {
             Ok(msgs) => msgs,
             Err(e) => {
                 tracing::error!("Failed to fetch unscanned messages: {}", e);
@@ -552,8 +829,15 @@ async fn scan_for_media_recommendations(db: Database) -> Result<()> {
             messages_scanned += 1;
             current_last_id = *msg_id;
 
+            if opted_out.contains(user_id) {
+                continue;
+            }
+
             // Detect media recommendations
-            let recommendations = detector.detect_media(&content);
+            let mut recommendations = detector.detect_media(content);
+            tuning.apply(&mut recommendations);
+            steam::resolve_titles(db, &mut recommendations).await;
+            remove_blacklisted(&mut recommendations, &blacklist);
 
             for rec in recommendations {
                 if let Err(e) = db
@@ -566,6 +850,7 @@ async fn scan_for_media_recommendations(db: Database) -> Result<()> {
                         &rec.title,
                         rec.url.as_deref(),
                         rec.confidence,
+                        DETECTOR_VERSION,
                         *timestamp,
                     )
                     .await
@@ -577,6 +862,11 @@ async fn scan_for_media_recommendations(db: Database) -> Result<()> {
             }
         }
 
+        // Refresh the in-progress flag so long scans aren't mistaken for stale ones
+        db.set_setting(SCAN_IN_PROGRESS_KEY, &chrono::Utc::now().to_rfc3339())
+            .await
+            .ok();
+
         // Update checkpoint after each batch
         if let Err(e) = db
             .update_media_scan_checkpoint(current_last_id, messages_scanned, recommendations_found)
@@ -586,7 +876,7 @@ async fn scan_for_media_recommendations(db: Database) -> Result<()> {
         }
 
         // Log progress
-        if messages_scanned % 10000 == 0 {
+        if messages_scanned.is_multiple_of(10000) {
             info!(
                 "Media scan progress: {} messages scanned, {} recommendations found",
                 messages_scanned, recommendations_found
@@ -594,7 +884,7 @@ async fn scan_for_media_recommendations(db: Database) -> Result<()> {
         }
 
         // If we got less than a full batch, we're done
-        if messages.len() < BATCH_SIZE as usize {
+        if messages.len() < batch_size as usize {
             break;
         }
     }
@@ -604,7 +894,7 @@ async fn scan_for_media_recommendations(db: Database) -> Result<()> {
         messages_scanned, recommendations_found
     );
 
-    Ok(())
+    Ok((messages_scanned, recommendations_found))
 }
 
 async fn cleanup_old_giphy_cache(db: Database) -> Result<()> {
@@ -627,3 +917,243 @@ async fn cleanup_old_giphy_cache(db: Database) -> Result<()> {
         }
     }
 }
+
+async fn refresh_giphy_cache(db: Database) -> Result<()> {
+    info!("Starting GIPHY cache refresh job");
+
+    // GIPHY caps search results per request at 50
+    const MAX_BATCH_SIZE: u32 = 50;
+    // Keep well under the GIPHY API rate limit
+    const REQUEST_DELAY_MS: u64 = 2000;
+
+    let client = match crate::giphy::GiphyClient::new(db.clone()) {
+        Ok(client) => client,
+        Err(_) => {
+            info!("GIPHY_API_KEY not set, skipping GIPHY cache refresh");
+            return Ok(());
+        }
+    };
+
+    let cache_target =
+        u32::try_from(Settings::new(&db).get_u64("giphy_cache_target").await).unwrap_or(u32::MAX);
+
+    let search_terms = db.get_active_giphy_search_terms().await?;
+    let mut requests_made = 0;
+
+    for search_term in search_terms {
+        let cache_size = db.get_cache_size(&search_term).await?;
+        if cache_size >= cache_target {
+            continue;
+        }
+
+        if requests_made > 0 {
+            tokio::time::sleep(tokio::time::Duration::from_millis(REQUEST_DELAY_MS)).await;
+        }
+        requests_made += 1;
+
+        // Page past what's already cached so the refresh picks up new GIFs
+        let needed = (cache_target - cache_size).min(MAX_BATCH_SIZE);
+        let response = match client.search(&search_term, needed, cache_size).await {
+            Ok(response) => response,
+            Err(e) => {
+                tracing::warn!("Failed to search GIPHY for '{}': {}", search_term, e);
+                continue;
+            }
+        };
+
+        for gif in &response.data {
+            if let Err(e) = db.cache_giphy_gif(&search_term, gif).await {
+                tracing::warn!("Failed to cache GIF {}: {}", gif.id, e);
+            }
+        }
+
+        let new_size = db.get_cache_size(&search_term).await?;
+        info!(
+            "Cached {} new GIFs for search term '{}' ({} / {} cached)",
+            new_size.saturating_sub(cache_size),
+            search_term,
+            new_size,
+            cache_target
+        );
+    }
+
+    info!(
+        "GIPHY cache refresh complete. {} API requests made",
+        requests_made
+    );
+
+    Ok(())
+}
+
+async fn send_watchlist_reminders(ctx: Arc<Context>, db: Database) -> Result<()> {
+    let reminders = db.get_due_watchlist_reminders(50).await?;
+
+    if reminders.is_empty() {
+        return Ok(());
+    }
+
+    info!("Sending {} due watchlist reminders", reminders.len());
+
+    for (watchlist_id, user_id, media_type, title, url) in reminders {
+        let target = UserId::new(user_id);
+
+        // Only DM users we still share a guild with
+        let shares_guild = ctx.cache.guilds().into_iter().any(|guild_id| {
+            ctx.cache
+                .guild(guild_id)
+                .map(|guild| guild.members.contains_key(&target))
+                .unwrap_or(false)
+        });
+
+        if shares_guild {
+            let content = format!(
+                "⏰ Don't forget to watch **{}** ({}) from your watchlist!{}",
+                title,
+                media_type,
+                url.as_ref().map(|u| format!("\n{}", u)).unwrap_or_default()
+            );
+
+            match target
+                .direct_message(&ctx.http, CreateMessage::new().content(content))
+                .await
+            {
+                Ok(_) => info!("Sent watchlist reminder for '{}' to {}", title, user_id),
+                Err(e) => tracing::warn!(
+                    "Failed to send watchlist reminder for '{}' to {}: {}",
+                    title,
+                    user_id,
+                    e
+                ),
+            }
+        } else {
+            info!(
+                "Skipping watchlist reminder for '{}' - user {} no longer shares a guild",
+                title, user_id
+            );
+        }
+
+        // Reminders are one-shot, clear it whether or not the DM went through
+        if let Err(e) = db.clear_watchlist_reminder(watchlist_id).await {
+            tracing::error!("Failed to clear watchlist reminder {}: {}", watchlist_id, e);
+        }
+    }
+
+    Ok(())
+}
+
+async fn send_event_reminders(ctx: Arc<Context>, db: Database) -> Result<()> {
+    // Events starting in the next 15 minutes
+    let events = db.get_events_needing_reminder(15).await?;
+
+    for (event_id, guild_id, name, start_time, location) in events {
+        // Mark first so an overlapping run can't remind the same users twice
+        db.mark_event_reminded(event_id).await?;
+
+        let users = db.get_event_interested_users(event_id).await?;
+        let guild_name = ctx
+            .cache
+            .guild(serenity::all::GuildId::new(guild_id))
+            .map(|g| g.name.clone())
+            .unwrap_or_else(|| "your server".to_string());
+
+        info!(
+            "Sending reminders for event '{}' ({}) to {} interested users",
+            name,
+            event_id,
+            users.len()
+        );
+
+        let content = format!(
+            "📅 **{}** in {} starts <t:{}:R>!{}",
+            name,
+            guild_name,
+            start_time.timestamp(),
+            location
+                .as_ref()
+                .map(|l| format!("\n📍 {}", l))
+                .unwrap_or_default()
+        );
+
+        for user_id in users {
+            if let Err(e) = UserId::new(user_id)
+                .direct_message(&ctx.http, CreateMessage::new().content(content.clone()))
+                .await
+            {
+                tracing::warn!(
+                    "Failed to send event reminder for {} to {}: {}",
+                    event_id,
+                    user_id,
+                    e
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// When the weekly digest was last posted, if ever.
+pub async fn digest_last_posted(db: &Database) -> Result<Option<DateTime<Utc>>> {
+    Ok(db
+        .get_setting(DIGEST_LAST_POSTED_KEY)
+        .await?
+        .and_then(|value| DateTime::parse_from_rfc3339(&value).ok())
+        .map(|posted| posted.with_timezone(&Utc)))
+}
+
+async fn post_weekly_digest(ctx: Arc<Context>, db: Database) -> Result<()> {
+    if !Settings::new(&db).get_bool(DIGEST_ENABLED_KEY).await {
+        return Ok(());
+    }
+
+    let Some(channel_id) = db
+        .get_setting(DIGEST_CHANNEL_KEY)
+        .await?
+        .and_then(|value| value.parse::<u64>().ok())
+    else {
+        return Ok(());
+    };
+
+    let now = Utc::now();
+    if let Some(last_posted) = digest_last_posted(&db).await? {
+        if now - last_posted < chrono::Duration::days(DIGEST_INTERVAL_DAYS) {
+            return Ok(());
+        }
+    }
+
+    let items = db
+        .get_top_recommendations(10, DIGEST_INTERVAL_DAYS as i32, None, 2)
+        .await?;
+
+    if items.is_empty() {
+        info!("No recommendations this week, skipping digest");
+    } else {
+        let mut embed = CreateEmbed::new()
+            .title("🔥 Weekly Digest: Top Recommendations")
+            .description("What everyone's been talking about this past week")
+            .colour(Colour::GOLD)
+            .timestamp(now);
+
+        for (media_type, title, _avg_confidence, mentions, url) in &items {
+            let (name, value) =
+                top_recommendation_field(media_type, title, *mentions, url.as_deref());
+            embed = embed.field(name, value, false);
+        }
+
+        // Leave last-posted untouched on failure so the next hourly run retries
+        ChannelId::new(channel_id)
+            .send_message(&ctx.http, CreateMessage::new().embed(embed))
+            .await?;
+
+        info!(
+            "Posted weekly digest with {} recommendations to channel {}",
+            items.len(),
+            channel_id
+        );
+    }
+
+    db.set_setting(DIGEST_LAST_POSTED_KEY, &now.to_rfc3339())
+        .await?;
+
+    Ok(())
+}
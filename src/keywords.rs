@@ -0,0 +1,100 @@
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tracing::warn;
+
+/// Compiled keywords kept before the cache is cleared and rebuilt from the lists in use.
+const MAX_CACHED_KEYWORDS: usize = 10_000;
+
+/// Whole-word keyword matching shared by keyword reactions and watched keywords. Each
+/// keyword is compiled once and reused for every later message.
+pub struct KeywordMatcher {
+    // `None` for keywords that failed to compile, so they aren't retried every message
+    compiled: Mutex<HashMap<String, Option<Arc<Regex>>>>,
+}
+
+impl KeywordMatcher {
+    pub fn new() -> Self {
+        Self {
+            compiled: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Whether `keyword` appears in `content` as a whole word or phrase, ignoring case.
+    /// `*` matches any run of letters, so `*scam*` also catches "scammer", and spaces
+    /// match any run of whitespace.
+    pub fn matches(&self, content: &str, keyword: &str) -> bool {
+        if keyword.trim_matches('*').trim().is_empty() {
+            return false;
+        }
+
+        let regex = {
+            let Ok(mut compiled) = self.compiled.lock() else {
+                return false;
+            };
+            if compiled.len() >= MAX_CACHED_KEYWORDS && !compiled.contains_key(keyword) {
+                compiled.clear();
+            }
+            compiled
+                .entry(keyword.to_string())
+                .or_insert_with(|| compile(keyword).map(Arc::new))
+                .clone()
+        };
+
+        regex.is_some_and(|regex| regex.is_match(content))
+    }
+}
+
+fn compile(keyword: &str) -> Option<Regex> {
+    let body = keyword
+        .split('*')
+        .map(regex::escape)
+        .collect::<Vec<_>>()
+        .join(r"\w*")
+        .replace(' ', r"\s+");
+
+    match Regex::new(&format!(r"(?i)(?:^|\W){}(?:$|\W)", body)) {
+        Ok(regex) => Some(regex),
+        Err(e) => {
+            warn!("Invalid keyword '{}': {}", keyword, e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_whole_words_only() {
+        let matcher = KeywordMatcher::new();
+        assert!(matcher.matches("I love Pizza!", "pizza"));
+        assert!(matcher.matches("pizza", "pizza"));
+        assert!(!matcher.matches("pizzas are great", "pizza"));
+        assert!(!matcher.matches("my_pizza", "pizza"));
+    }
+
+    #[test]
+    fn matches_phrases_across_whitespace() {
+        let matcher = KeywordMatcher::new();
+        assert!(matcher.matches("free   nitro here", "free nitro"));
+        assert!(!matcher.matches("freenitro", "free nitro"));
+    }
+
+    #[test]
+    fn wildcards_match_word_characters() {
+        let matcher = KeywordMatcher::new();
+        assert!(matcher.matches("what a scammer", "*scam*"));
+        assert!(matcher.matches("scam", "*scam*"));
+        assert!(!matcher.matches("scam artist", "scam*er"));
+        assert!(!matcher.matches("anything", "**"));
+    }
+
+    #[test]
+    fn special_characters_are_literal() {
+        let matcher = KeywordMatcher::new();
+        assert!(matcher.matches("learning c++ today", "c++"));
+        assert!(!matcher.matches("learning cxx today", "c.."));
+    }
+}
@@ -1,10 +1,189 @@
+use crate::db::Database;
+use crate::settings::Settings;
 use anyhow::Result;
+use futures_util::TryStreamExt;
+use sha2::{Digest, Sha256};
 use std::path::{Path, PathBuf};
 use tokio::fs;
-use tokio::io::AsyncWriteExt;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio_util::io::StreamReader;
 use tracing::{error, info};
 use uuid::Uuid;
 
+/// Hex-encoded SHA-256 of a file's contents, used to detect duplicate media.
+pub fn content_hash(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// Returns stored copies of the given content that still exist on disk,
+/// pruning any hash records whose files have since been removed.
+pub async fn existing_copies(db: &Database, content_hash: &str) -> Vec<String> {
+    let mut copies = Vec::new();
+
+    for path in db
+        .get_media_hash_paths(content_hash)
+        .await
+        .unwrap_or_default()
+    {
+        if fs::metadata(&path).await.is_ok() {
+            copies.push(path);
+        } else {
+            db.remove_media_hash_path(&path).await.ok();
+        }
+    }
+
+    copies
+}
+
+/// Sets a file's modification time to now.
+async fn touch(path: &str) -> std::io::Result<()> {
+    let file = fs::OpenOptions::new()
+        .write(true)
+        .open(path)
+        .await?
+        .into_std()
+        .await;
+    tokio::task::spawn_blocking(move || file.set_modified(std::time::SystemTime::now())).await?
+}
+
+/// Setting key for the largest attachment the bot will download.
+pub const MAX_ATTACHMENT_BYTES_KEY: &str = "max_attachment_bytes";
+
+/// Reads the configured download limit; `KNOWN_SETTINGS` holds the default.
+pub async fn max_attachment_bytes(db: &Database) -> u64 {
+    Settings::new(db).get_u64(MAX_ATTACHMENT_BYTES_KEY).await
+}
+
+/// Streams `url` into `temp_path` chunk by chunk, returning its size and content hash.
+/// Bodies over `max_bytes` and failed downloads are removed rather than left half-written.
+pub async fn download_to_file(
+    url: &str,
+    temp_path: &Path,
+    max_bytes: u64,
+) -> Result<(u64, String)> {
+    let result = stream_to_file(url, temp_path, max_bytes).await;
+    if result.is_err() {
+        fs::remove_file(temp_path).await.ok();
+    }
+    result
+}
+
+async fn stream_to_file(url: &str, temp_path: &Path, max_bytes: u64) -> Result<(u64, String)> {
+    let response = reqwest::get(url).await?;
+
+    if !response.status().is_success() {
+        return Err(anyhow::anyhow!("HTTP {}", response.status()));
+    }
+
+    if let Some(length) = response.content_length() {
+        if length > max_bytes {
+            return Err(anyhow::anyhow!(
+                "file is too large ({} bytes, limit {})",
+                length,
+                max_bytes
+            ));
+        }
+    }
+
+    // Hash while streaming, reading one byte past the limit to detect oversized bodies
+    let mut hasher = Sha256::new();
+    let stream = response
+        .bytes_stream()
+        .map_err(std::io::Error::other)
+        .inspect_ok(|chunk| hasher.update(chunk));
+    let mut reader = StreamReader::new(stream).take(max_bytes + 1);
+
+    let mut file = fs::File::create(temp_path).await?;
+    let size = tokio::io::copy(&mut reader, &mut file).await?;
+    file.flush().await?;
+    drop(reader);
+
+    if size > max_bytes {
+        return Err(anyhow::anyhow!(
+            "file is too large (over {} bytes)",
+            max_bytes
+        ));
+    }
+
+    Ok((size, format!("{:x}", hasher.finalize())))
+}
+
+/// Reads the first few KiB of a file, enough for magic-byte sniffing.
+pub async fn file_head(path: &Path) -> Result<Vec<u8>> {
+    let file = fs::File::open(path).await?;
+    let mut head = Vec::with_capacity(8192);
+    file.take(8192).read_to_end(&mut head).await?;
+    Ok(head)
+}
+
+/// Media family implied by a content type, falling back to the file extension.
+fn claimed_kind(content_type: Option<&str>, filename: &str) -> Option<infer::MatcherType> {
+    match content_type {
+        Some(ct) if ct.starts_with("image/") => return Some(infer::MatcherType::Image),
+        Some(ct) if ct.starts_with("video/") => return Some(infer::MatcherType::Video),
+        Some(ct) if ct.starts_with("audio/") => return Some(infer::MatcherType::Audio),
+        _ => {}
+    }
+
+    let extension = Path::new(filename)
+        .extension()
+        .and_then(|ext| ext.to_str())?
+        .to_lowercase();
+    match extension.as_str() {
+        "png" | "jpg" | "jpeg" | "gif" | "webp" | "bmp" | "avif" | "heic" => {
+            Some(infer::MatcherType::Image)
+        }
+        "mp4" | "webm" | "mov" | "mkv" | "avi" | "m4v" => Some(infer::MatcherType::Video),
+        "mp3" | "ogg" | "wav" | "flac" | "m4a" | "opus" => Some(infer::MatcherType::Audio),
+        _ => None,
+    }
+}
+
+/// Checks downloaded bytes against their magic numbers. Executables are always rejected,
+/// and files claiming to be an image, video or audio must actually sniff as that family.
+/// Audio and video are treated as one family since containers like MP4, WebM and Ogg hold either.
+pub fn validate_media(bytes: &[u8], content_type: Option<&str>, filename: &str) -> Result<()> {
+    use infer::MatcherType::{Audio, Video};
+
+    let sniffed = infer::get(bytes);
+
+    if let Some(kind) = sniffed.filter(|kind| kind.matcher_type() == infer::MatcherType::App) {
+        return Err(anyhow::anyhow!(
+            "{} is an executable ({})",
+            filename,
+            kind.mime_type()
+        ));
+    }
+
+    if let Some(claimed) = claimed_kind(content_type, filename) {
+        match sniffed {
+            Some(kind)
+                if kind.matcher_type() == claimed
+                    || matches!(
+                        (kind.matcher_type(), claimed),
+                        (Audio | Video, Audio | Video)
+                    ) => {}
+            Some(kind) => {
+                return Err(anyhow::anyhow!(
+                    "{} claims to be {} but contains {}",
+                    filename,
+                    format!("{:?}", claimed).to_lowercase(),
+                    kind.mime_type()
+                ))
+            }
+            None => {
+                return Err(anyhow::anyhow!(
+                    "{} claims to be {} but its contents are unrecognised",
+                    filename,
+                    format!("{:?}", claimed).to_lowercase()
+                ))
+            }
+        }
+    }
+
+    Ok(())
+}
+
 #[derive(Clone)]
 pub struct MediaCache {
     cache_dir: PathBuf,
@@ -31,6 +210,7 @@ impl MediaCache {
 
     pub async fn download_attachment(
         &self,
+        db: &Database,
         url: &str,
         filename: &str,
         content_type: Option<&str>,
@@ -61,21 +241,51 @@ impl MediaCache {
 
         let file_path = self.cache_dir.join(subdir).join(&unique_filename);
 
-        // Download the file
-        let response = reqwest::get(url).await?;
+        // Stream the download to a temp file, then make sure it is what it claims to be
+        let temp_path = self
+            .cache_dir
+            .join(subdir)
+            .join(format!(".{}.part", Uuid::new_v4()));
+        let (size, hash) =
+            download_to_file(url, &temp_path, max_attachment_bytes(db).await).await?;
+        if let Err(e) = file_head(&temp_path)
+            .await
+            .and_then(|head| validate_media(&head, content_type, filename))
+        {
+            fs::remove_file(&temp_path).await.ok();
+            return Err(e);
+        }
 
-        if !response.status().is_success() {
-            return Err(anyhow::anyhow!(
-                "Failed to download attachment: HTTP {}",
-                response.status()
-            ));
+        // Reuse an existing cached copy of identical content instead of keeping this one
+        let cache_prefix = self.cache_dir.to_string_lossy().to_string();
+        if let Some(existing) = existing_copies(db, &hash)
+            .await
+            .into_iter()
+            .find(|path| path.starts_with(&cache_prefix))
+        {
+            info!(
+                "Attachment {} is a duplicate of {}, skipping write",
+                filename, existing
+            );
+            fs::remove_file(&temp_path).await.ok();
+            // The age-based cleanup goes by mtime, so count this reuse as a fresh write
+            if let Err(e) = touch(&existing).await {
+                error!("Failed to refresh mtime of {}: {}", existing, e);
+            }
+            return Ok(PathBuf::from(existing));
         }
 
-        // Stream to file
-        let mut file = fs::File::create(&file_path).await?;
-        let bytes = response.bytes().await?;
-        file.write_all(&bytes).await?;
-        file.flush().await?;
+        if let Err(e) = fs::rename(&temp_path, &file_path).await {
+            fs::remove_file(&temp_path).await.ok();
+            return Err(e.into());
+        }
+
+        if let Err(e) = db
+            .record_media_hash(&hash, &file_path.to_string_lossy(), size)
+            .await
+        {
+            error!("Failed to record media hash for {:?}: {}", file_path, e);
+        }
 
         info!("Downloaded attachment {} to {:?}", filename, file_path);
 
@@ -122,6 +332,121 @@ impl MediaCache {
         Ok(deleted_count)
     }
 
+    pub async fn total_size(&self) -> Result<u64> {
+        let mut total = 0;
+
+        let subdirs = ["images", "videos", "audio", "documents", "other"];
+        for subdir in subdirs {
+            let dir_path = self.cache_dir.join(subdir);
+
+            if !dir_path.exists() {
+                continue;
+            }
+
+            let mut entries = fs::read_dir(&dir_path).await?;
+
+            while let Some(entry) = entries.next_entry().await? {
+                if let Ok(metadata) = entry.metadata().await {
+                    if metadata.is_file() {
+                        total += metadata.len();
+                    }
+                }
+            }
+        }
+
+        Ok(total)
+    }
+
+    /// Evicts the least recently cached files until the cache fits within `max_bytes`.
+    /// Returns the total cache size before eviction and the number of bytes freed.
+    pub async fn enforce_size_limit(&self, db: &Database, max_bytes: u64) -> Result<(u64, u64)> {
+        let total = self.total_size().await?;
+
+        if total <= max_bytes {
+            return Ok((total, 0));
+        }
+
+        let mut remaining = total;
+        let mut freed = 0;
+
+        for (attachment_id, local_path) in db.get_cached_media_oldest_first().await? {
+            if remaining <= max_bytes {
+                break;
+            }
+
+            // A deduplicated file still used by a newer attachment stays; only this
+            // row lets go of it, and the last row to go deletes it
+            match db.count_attachments_with_local_path(&local_path).await {
+                Ok(1) => {}
+                Ok(_) => {
+                    if let Err(e) = db.clear_local_path(attachment_id).await {
+                        error!(
+                            "Failed to clear local path for attachment {}: {}",
+                            attachment_id, e
+                        );
+                    }
+                    continue;
+                }
+                Err(e) => {
+                    error!("Failed to check other uses of {}: {}", local_path, e);
+                    continue;
+                }
+            }
+
+            let file_path = self.cache_dir.join(&local_path);
+            let file_size = match fs::metadata(&file_path).await {
+                Ok(metadata) => metadata.len(),
+                // Already gone from disk, just clear the stale reference
+                Err(_) => 0,
+            };
+
+            if file_size > 0 {
+                if let Err(e) = fs::remove_file(&file_path).await {
+                    error!("Failed to evict cached file {:?}: {}", file_path, e);
+                    continue;
+                }
+                remaining = remaining.saturating_sub(file_size);
+                freed += file_size;
+            }
+
+            if let Err(e) = db.clear_local_path(attachment_id).await {
+                error!(
+                    "Failed to clear local path for attachment {}: {}",
+                    attachment_id, e
+                );
+            }
+        }
+
+        Ok((total, freed))
+    }
+
+    /// Deletes the cached files at `local_paths` that no attachment row references any more.
+    /// Deduplicated files still used by someone else's attachment are kept. Returns how many
+    /// files were removed.
+    pub async fn remove_unreferenced(&self, db: &Database, local_paths: &[String]) -> usize {
+        let mut removed = 0;
+
+        for local_path in local_paths {
+            match db.count_attachments_with_local_path(local_path).await {
+                Ok(0) => {}
+                Ok(_) => continue,
+                Err(e) => {
+                    error!("Failed to check other uses of {}: {}", local_path, e);
+                    continue;
+                }
+            }
+
+            let file_path = self.cache_dir.join(local_path);
+            match fs::remove_file(&file_path).await {
+                Ok(()) => removed += 1,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => error!("Failed to delete cached file {:?}: {}", file_path, e),
+            }
+        }
+
+        removed
+    }
+
     pub fn get_relative_path(&self, full_path: &Path) -> Option<String> {
         full_path
             .strip_prefix(&self.cache_dir)
@@ -1,17 +1,40 @@
 use regex::Regex;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+
+use crate::db::Database;
+use crate::settings::Settings;
+
+/// Revision of the detection patterns below. Bump this whenever they change so
+/// `/scan reset purge_old:true` can drop recommendations made by older patterns.
+pub const DETECTOR_VERSION: u32 = 5;
+
+/// Confidence for titles read from a store or series link.
+const LINK_CONFIDENCE: f32 = 0.95;
+
+/// Confidence for titles read from an episode link, whose slug may name the episode
+/// rather than the series.
+const EPISODE_LINK_CONFIDENCE: f32 = 0.5;
+
+/// Every `media_type` the detector can emit. Each has a `detector_confidence_{type}` setting.
+pub const DETECTED_TYPES: [&str; 4] = ["anime", "tv_show", "game", "youtube"];
 
 pub struct MediaRecommendation {
     pub media_type: &'static str,
     pub title: String,
     pub url: Option<String>,
     pub confidence: f32,
+    /// Set for Steam store links so `steam::resolve_titles` can swap in the store name.
+    pub steam_app_id: Option<u64>,
 }
 
 pub struct MediaDetector {
     anime_patterns: Vec<Regex>,
     tv_show_patterns: Vec<Regex>,
     game_patterns: Vec<Regex>,
+    anime_link_patterns: Vec<Regex>,
+    crunchyroll_watch_pattern: Regex,
+    steam_pattern: Regex,
+    game_store_patterns: Vec<Regex>,
     youtube_pattern: Regex,
     url_pattern: Regex,
 }
@@ -41,6 +64,25 @@ impl MediaDetector {
                 Regex::new(r"(?i)(?:got|bought|downloaded)\s+([A-Za-z0-9\s:\-!?']+?)\s+(?:on|from)\s+(?:Steam|Epic|Xbox|PlayStation|Switch)").unwrap(),
                 Regex::new(r"(?i)([A-Za-z0-9\s:\-!?']+?)\s+(?:gameplay|walkthrough|guide|review)").unwrap(),
             ],
+            // Anime streaming links; the first capture group that matched holds the slug
+            anime_link_patterns: vec![
+                // crunchyroll.com/[locale/]series/<id>/<slug>
+                Regex::new(r"(?i)https?://(?:www\.)?crunchyroll\.com/(?:[a-z]{2}(?:-[a-z]{2})?/)?series/[a-z0-9]+/([a-z0-9\-]+)").unwrap(),
+                // Older crunchyroll.com/<series-slug>/episode-... links
+                Regex::new(r"(?i)https?://(?:www\.)?crunchyroll\.com/(?:[a-z]{2}(?:-[a-z]{2})?/)?([a-z0-9\-]+)/episode-[a-z0-9\-]*").unwrap(),
+                Regex::new(r"(?i)https?://(?:www\.)?funimation\.com/(?:[a-z]{2}(?:-[a-z]{2})?/)?(?:shows|v)/([a-z0-9\-]+)").unwrap(),
+                Regex::new(r"(?i)https?://(?:www\.)?hidive\.com/(?:tv|stream)/([a-z0-9\-]+)").unwrap(),
+            ],
+            // crunchyroll.com/[locale/]watch/<id>/<slug>. The slug names the episode rather
+            // than the series, so these are recorded at reduced confidence.
+            crunchyroll_watch_pattern: Regex::new(r"(?i)https?://(?:www\.)?crunchyroll\.com/(?:[a-z]{2}(?:-[a-z]{2})?/)?watch/[a-z0-9]+/([a-z0-9\-]+)").unwrap(),
+            // Steam store links: app ID, then the optional name slug Steam appends
+            steam_pattern: Regex::new(r"(?i)https?://store\.steampowered\.com/app/(\d+)(?:/([a-z0-9_\-]+))?").unwrap(),
+            // Other game stores, with the slug in the first capture group
+            game_store_patterns: vec![
+                Regex::new(r"(?i)https?://store\.epicgames\.com/(?:[a-z]{2}(?:-[a-z]{2})?/)?p/([a-z0-9\-]+)").unwrap(),
+                Regex::new(r"(?i)https?://(?:www\.)?gog\.com/(?:[a-z]{2}/)?game/([a-z0-9_\-]+)").unwrap(),
+            ],
             // YouTube pattern
             youtube_pattern: Regex::new(r"(?i)(?:https?://)?(?:www\.)?(?:youtube\.com/watch\?v=|youtu\.be/|youtube\.com/shorts/)([A-Za-z0-9_\-]+)").unwrap(),
             // General URL pattern
@@ -69,6 +111,7 @@ impl MediaDetector {
                             title,
                             url,
                             confidence: 0.8,
+                            steam_app_id: None,
                         });
                     }
                 }
@@ -91,6 +134,7 @@ impl MediaDetector {
                             title,
                             url,
                             confidence: 0.7,
+                            steam_app_id: None,
                         });
                     }
                 }
@@ -113,12 +157,96 @@ impl MediaDetector {
                             title,
                             url,
                             confidence: 0.7,
+                            steam_app_id: None,
                         });
                     }
                 }
             }
         }
 
+        // Check for anime streaming links. These name the show outright, so they beat the
+        // phrase patterns above.
+        for pattern in &self.anime_link_patterns {
+            for cap in pattern.captures_iter(content) {
+                let (Some(url), Some(slug)) = (cap.get(0), cap.get(1)) else {
+                    continue;
+                };
+                // Purely numeric slugs are IDs, not titles
+                if !slug.as_str().chars().any(|c| c.is_alphabetic()) {
+                    continue;
+                }
+
+                self.push_link_recommendation(
+                    &mut recommendations,
+                    &mut found_titles,
+                    "anime",
+                    Self::prettify_slug(slug.as_str()),
+                    url.as_str(),
+                    LINK_CONFIDENCE,
+                    None,
+                );
+            }
+        }
+        for cap in self.crunchyroll_watch_pattern.captures_iter(content) {
+            let (Some(url), Some(slug)) = (cap.get(0), cap.get(1)) else {
+                continue;
+            };
+            if !slug.as_str().chars().any(|c| c.is_alphabetic()) {
+                continue;
+            }
+
+            self.push_link_recommendation(
+                &mut recommendations,
+                &mut found_titles,
+                "anime",
+                Self::prettify_slug(slug.as_str()),
+                url.as_str(),
+                EPISODE_LINK_CONFIDENCE,
+                None,
+            );
+        }
+
+        // Check for game store links. Steam URLs may carry only the app ID, so the title
+        // falls back to "Steam App {id}" until `steam::resolve_titles` looks it up.
+        for cap in self.steam_pattern.captures_iter(content) {
+            let (Some(url), Some(app_id)) = (
+                cap.get(0),
+                cap.get(1).and_then(|id| id.as_str().parse::<u64>().ok()),
+            ) else {
+                continue;
+            };
+            let title = cap
+                .get(2)
+                .map(|slug| Self::prettify_slug(slug.as_str()))
+                .filter(|title| !title.is_empty())
+                .unwrap_or_else(|| format!("Steam App {}", app_id));
+            self.push_link_recommendation(
+                &mut recommendations,
+                &mut found_titles,
+                "game",
+                title,
+                url.as_str(),
+                LINK_CONFIDENCE,
+                Some(app_id),
+            );
+        }
+        for pattern in &self.game_store_patterns {
+            for cap in pattern.captures_iter(content) {
+                let (Some(url), Some(slug)) = (cap.get(0), cap.get(1)) else {
+                    continue;
+                };
+                self.push_link_recommendation(
+                    &mut recommendations,
+                    &mut found_titles,
+                    "game",
+                    Self::prettify_slug(slug.as_str()),
+                    url.as_str(),
+                    LINK_CONFIDENCE,
+                    None,
+                );
+            }
+        }
+
         // Check for YouTube videos
         for cap in self.youtube_pattern.captures_iter(content) {
             if let Some(video_id) = cap.get(1) {
@@ -132,6 +260,7 @@ impl MediaDetector {
                         title,
                         url: Some(url),
                         confidence: 1.0,
+                        steam_app_id: None,
                     });
                 }
             }
@@ -171,6 +300,130 @@ impl MediaDetector {
         recommendations
     }
 
+    /// Guesses a watchlist media type from a link, for items added as `other`.
+    /// Only sites that clearly host one kind of media are recognised.
+    pub fn infer_type_from_url(&self, url: &str) -> Option<&'static str> {
+        if self.youtube_pattern.is_match(url) {
+            return Some("youtube");
+        }
+
+        let without_scheme = url
+            .trim()
+            .split_once("://")
+            .map(|(_, rest)| rest)
+            .unwrap_or(url.trim());
+        let (host, path) = without_scheme
+            .split_once('/')
+            .unwrap_or((without_scheme, ""));
+        let host = host.to_lowercase();
+        let host = host.strip_prefix("www.").unwrap_or(&host);
+        let on = |domain: &str| host == domain || host.ends_with(&format!(".{}", domain));
+
+        let domains: [(&[&str], &'static str); 5] = [
+            (&["youtube.com", "youtu.be"], "youtube"),
+            (
+                &[
+                    "myanimelist.net",
+                    "anilist.co",
+                    "crunchyroll.com",
+                    "kitsu.app",
+                    "kitsu.io",
+                ],
+                "anime",
+            ),
+            (
+                &[
+                    "steampowered.com",
+                    "steamcommunity.com",
+                    "epicgames.com",
+                    "gog.com",
+                    "itch.io",
+                ],
+                "game",
+            ),
+            (&["letterboxd.com"], "movie"),
+            (
+                &[
+                    "spotify.com",
+                    "music.apple.com",
+                    "soundcloud.com",
+                    "bandcamp.com",
+                ],
+                "music",
+            ),
+        ];
+        if let Some((_, media_type)) = domains
+            .iter()
+            .find(|(hosts, _)| hosts.iter().any(|domain| on(domain)))
+        {
+            return Some(media_type);
+        }
+
+        // TMDB and TVDB cover both films and shows, so go by the path
+        if on("themoviedb.org") || on("thetvdb.com") {
+            if path.starts_with("movie") {
+                return Some("movie");
+            }
+            if path.starts_with("tv") || path.starts_with("series") {
+                return Some("tv_show");
+            }
+        }
+
+        if on("tvmaze.com") {
+            return Some("tv_show");
+        }
+
+        None
+    }
+
+    /// Records a title taken from a store or streaming link. A phrase match with the same
+    /// title is upgraded in place rather than duplicated, since the link is stronger evidence.
+    #[allow(clippy::too_many_arguments)]
+    fn push_link_recommendation(
+        &self,
+        recommendations: &mut Vec<MediaRecommendation>,
+        found_titles: &mut HashMap<String, &'static str>,
+        media_type: &'static str,
+        title: String,
+        url: &str,
+        confidence: f32,
+        steam_app_id: Option<u64>,
+    ) {
+        if let Some(existing) = recommendations
+            .iter_mut()
+            .find(|rec| rec.media_type == media_type && rec.title.eq_ignore_ascii_case(&title))
+        {
+            existing.url = Some(url.to_string());
+            existing.confidence = existing.confidence.max(confidence);
+            existing.steam_app_id = existing.steam_app_id.or(steam_app_id);
+        } else if !found_titles.contains_key(&title) {
+            found_titles.insert(title.clone(), media_type);
+            recommendations.push(MediaRecommendation {
+                media_type,
+                title,
+                url: Some(url.to_string()),
+                confidence,
+                steam_app_id,
+            });
+        }
+    }
+
+    /// Turns a URL slug such as `demon-slayer-kimetsu-no-yaiba` or `Baldurs_Gate_3` into
+    /// a title like `Demon Slayer Kimetsu No Yaiba` or `Baldurs Gate 3`.
+    fn prettify_slug(slug: &str) -> String {
+        slug.split(['-', '_'])
+            .filter(|word| !word.is_empty())
+            .map(|word| {
+                let mut chars = word.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
     fn clean_title(&self, title: &str) -> String {
         let cleaned = title
             .trim()
@@ -196,10 +449,188 @@ impl MediaDetector {
         let search_end = (end + 50).min(content.len());
         let search_area = &content[search_start..search_end];
 
-        if let Some(url_match) = self.url_pattern.find(search_area) {
-            Some(url_match.as_str().to_string())
-        } else {
-            None
+        self.url_pattern
+            .find(search_area)
+            .map(|url_match| url_match.as_str().to_string())
+    }
+}
+
+/// Discord's limit on an embed field name, in characters.
+const MAX_EMBED_FIELD_NAME_CHARS: usize = 256;
+
+/// Emoji shown next to a media type in recommendation embeds.
+pub fn media_type_emoji(media_type: &str) -> &'static str {
+    match media_type {
+        "anime" => "🎌",
+        "tv_show" => "📺",
+        "movie" => "🎬",
+        "game" => "🎮",
+        "youtube" => "📹",
+        "music" => "🎵",
+        _ => "📋",
+    }
+}
+
+/// Shortens a title to fit an embed field name. Detected titles have no length cap, and
+/// one over the limit makes Discord reject the whole message.
+pub fn embed_field_name(title: &str) -> String {
+    if title.chars().count() <= MAX_EMBED_FIELD_NAME_CHARS {
+        return title.to_string();
+    }
+    let mut name: String = title.chars().take(MAX_EMBED_FIELD_NAME_CHARS - 1).collect();
+    name.push('…');
+    name
+}
+
+/// Embed field name and value for a top recommendation, as shown in the weekly digest
+/// and `/watchlist view top`.
+pub fn top_recommendation_field(
+    media_type: &str,
+    title: &str,
+    mentions: i64,
+    url: Option<&str>,
+) -> (String, String) {
+    let value = format!(
+        "{} {} | Mentioned {} times{}",
+        media_type_emoji(media_type),
+        media_type,
+        mentions,
+        url.map(|u| format!("\n[Link]({})", u)).unwrap_or_default()
+    );
+    (embed_field_name(title), value)
+}
+
+/// Key that groups spelling variants of one title, so "Baldur's Gate 3" and
+/// "baldurs-gate-3" count as one recommendation. Apostrophes are dropped, other
+/// punctuation becomes a space, whitespace is collapsed and the result lowercased.
+/// The `normalized_recommendation_titles` migration backfills with the same rules.
+pub fn normalize_title(title: &str) -> String {
+    let spaced: String = title
+        .chars()
+        .filter(|c| !matches!(c, '\'' | '‘' | '’'))
+        .map(|c| {
+            if c.is_ascii_punctuation() || matches!(c, '“' | '”') {
+                ' '
+            } else {
+                c
+            }
+        })
+        .collect();
+
+    spaced
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// The form blacklist terms are stored and compared in: lowercase with single spaces.
+pub fn blacklist_key(title: &str) -> String {
+    title
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Drops recommendations whose title is on the `media_detection_blacklist`, ignoring case.
+pub fn remove_blacklisted(
+    recommendations: &mut Vec<MediaRecommendation>,
+    blacklist: &HashSet<String>,
+) {
+    if blacklist.is_empty() {
+        return;
+    }
+    recommendations.retain(|rec| !blacklist.contains(&blacklist_key(&rec.title)));
+}
+
+/// Operator-tuned confidence multipliers, one per detected type, read from the
+/// `detector_confidence_{type}` settings. Load once per message or scan batch.
+pub struct ConfidenceTuning {
+    multipliers: HashMap<&'static str, f32>,
+}
+
+impl ConfidenceTuning {
+    pub async fn load(db: &Database) -> Self {
+        let settings = Settings::new(db);
+        let mut multipliers = HashMap::new();
+        for media_type in DETECTED_TYPES {
+            let key = format!("detector_confidence_{}", media_type);
+            multipliers.insert(media_type, settings.get_f64(&key).await as f32);
+        }
+
+        Self { multipliers }
+    }
+
+    /// Scales each recommendation's confidence by its type's multiplier, capped at 100%.
+    /// A multiplier of 0 stops that type from being recorded at all.
+    pub fn apply(&self, recommendations: &mut Vec<MediaRecommendation>) {
+        for rec in recommendations.iter_mut() {
+            let multiplier = self.multipliers.get(rec.media_type).copied().unwrap_or(1.0);
+            rec.confidence = (rec.confidence * multiplier).min(1.0);
         }
+        recommendations.retain(|rec| rec.confidence > 0.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn anime_titles(content: &str) -> Vec<String> {
+        MediaDetector::new()
+            .detect_media(content)
+            .into_iter()
+            .filter(|rec| rec.media_type == "anime")
+            .map(|rec| rec.title)
+            .collect()
+    }
+
+    #[test]
+    fn crunchyroll_series_link_uses_series_slug() {
+        let titles = anime_titles(
+            "https://www.crunchyroll.com/series/GY5P48XEY/demon-slayer-kimetsu-no-yaiba",
+        );
+        assert_eq!(titles, vec!["Demon Slayer Kimetsu No Yaiba"]);
+
+        let titles = anime_titles("https://crunchyroll.com/pt-br/series/G4PH0WXVJ/spy-x-family");
+        assert_eq!(titles, vec!["Spy X Family"]);
+    }
+
+    #[test]
+    fn crunchyroll_watch_link_has_reduced_confidence() {
+        let recommendations = MediaDetector::new()
+            .detect_media("https://www.crunchyroll.com/watch/GEVUZD021/cruelty");
+        assert_eq!(recommendations.len(), 1);
+        assert_eq!(recommendations[0].media_type, "anime");
+        assert_eq!(recommendations[0].title, "Cruelty");
+        assert_eq!(recommendations[0].confidence, EPISODE_LINK_CONFIDENCE);
+
+        let recommendations = MediaDetector::new().detect_media(
+            "https://www.crunchyroll.com/series/GY5P48XEY/demon-slayer-kimetsu-no-yaiba",
+        );
+        assert_eq!(recommendations[0].confidence, LINK_CONFIDENCE);
+    }
+
+    #[test]
+    fn funimation_shows_link_is_detected() {
+        let titles = anime_titles("https://www.funimation.com/shows/my-hero-academia/");
+        assert_eq!(titles, vec!["My Hero Academia"]);
+    }
+
+    #[test]
+    fn embed_field_name_caps_long_titles() {
+        assert_eq!(embed_field_name("Frieren"), "Frieren");
+
+        let long = "ア".repeat(300);
+        let name = embed_field_name(&long);
+        assert_eq!(name.chars().count(), 256);
+        assert!(name.ends_with('…'));
+    }
+
+    #[test]
+    fn hidive_tv_link_is_detected() {
+        let titles = anime_titles("https://www.hidive.com/tv/made-in-abyss");
+        assert_eq!(titles, vec!["Made In Abyss"]);
     }
 }
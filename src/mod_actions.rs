@@ -0,0 +1,79 @@
+use serde_json::json;
+use tracing::{info, warn};
+
+use crate::db::Database;
+
+/// Tracing target for audit events; `main` routes only these into `logs/audit.log`.
+pub const AUDIT_TARGET: &str = "audit";
+
+/// Setting holding the webhook URL moderation actions are mirrored to.
+pub const MOD_ACTION_WEBHOOK_KEY: &str = "mod_action_webhook_url";
+
+const WEBHOOK_TIMEOUT_SECS: u64 = 10;
+
+/// A moderation action that went through in one guild.
+pub struct ModAction<'a> {
+    pub action: &'a str,
+    pub actor_id: u64,
+    pub target_id: u64,
+    pub target_tag: &'a str,
+    pub guild_id: u64,
+    pub guild_name: &'a str,
+    pub reason: Option<&'a str>,
+    /// Only set for timeouts.
+    pub duration_minutes: Option<u64>,
+}
+
+/// Records a successful moderation action: writes a structured event to the audit log
+/// and mirrors it to `mod_action_webhook_url` when that is set. The POST runs in the
+/// background so a slow or broken webhook never holds up the action itself; failures
+/// are only logged.
+pub fn record(db: &Database, action: ModAction<'_>) {
+    info!(
+        target: AUDIT_TARGET,
+        action = action.action,
+        actor_id = action.actor_id,
+        target_id = action.target_id,
+        target_tag = action.target_tag,
+        guild_id = action.guild_id,
+        guild_name = action.guild_name,
+        reason = action.reason.unwrap_or(""),
+        duration_minutes = action.duration_minutes,
+        "moderation action"
+    );
+
+    let payload = json!({
+        "action": action.action,
+        "actor_id": action.actor_id.to_string(),
+        "target_id": action.target_id.to_string(),
+        "target": action.target_tag,
+        "guild_id": action.guild_id.to_string(),
+        "guild": action.guild_name,
+        "reason": action.reason,
+        "duration_minutes": action.duration_minutes,
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+    });
+    let db = db.clone();
+
+    tokio::spawn(async move {
+        let url = match db.get_setting(MOD_ACTION_WEBHOOK_KEY).await {
+            Ok(Some(url)) if !url.trim().is_empty() => url,
+            Ok(_) => return,
+            Err(e) => {
+                warn!("Failed to read {}: {}", MOD_ACTION_WEBHOOK_KEY, e);
+                return;
+            }
+        };
+
+        let result = reqwest::Client::new()
+            .post(url.trim())
+            .timeout(std::time::Duration::from_secs(WEBHOOK_TIMEOUT_SECS))
+            .json(&payload)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+        if let Err(e) = result {
+            warn!("Failed to send mod action webhook: {}", e);
+        }
+    });
+}
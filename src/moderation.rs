@@ -0,0 +1,506 @@
+use serenity::all::{Context, EditMember, Guild, GuildId, Permissions, RoleId, UserId};
+use tracing::info;
+
+use crate::db::Database;
+use crate::i18n;
+use crate::mod_actions::{self, ModAction};
+use crate::settings::Settings;
+
+/// Longest timeout Discord accepts: 28 days, in minutes.
+pub const MAX_TIMEOUT_MINUTES: u64 = 28 * 24 * 60;
+
+/// A moderation action shared by the slash and DM entry points.
+#[derive(Debug, Clone, Copy)]
+pub enum Action {
+    Kick,
+    Ban { delete_days: u8 },
+    Timeout { duration_minutes: u64 },
+}
+
+impl Action {
+    /// Name recorded in the audit log and mod action webhook.
+    fn name(self) -> &'static str {
+        match self {
+            Action::Kick => "kick",
+            Action::Ban { .. } => "ban",
+            Action::Timeout { .. } => "timeout",
+        }
+    }
+}
+
+/// Why a guild was skipped before calling Discord, from the bot's cached permissions.
+#[derive(Debug, Clone, Copy)]
+pub enum SkipReason {
+    MissingPermission(&'static str),
+    RoleTooHigh,
+    TargetIsOwner,
+    TargetIsAdmin,
+}
+
+impl SkipReason {
+    pub fn describe(self, locale: &str) -> String {
+        match self {
+            SkipReason::MissingPermission(permission) => i18n::t_with(
+                "moderation.missing_permission",
+                locale,
+                &[("permission", permission)],
+            ),
+            SkipReason::RoleTooHigh => i18n::t("moderation.role_too_high", locale),
+            SkipReason::TargetIsOwner => i18n::t("moderation.target_is_owner", locale),
+            SkipReason::TargetIsAdmin => i18n::t("moderation.target_is_admin", locale),
+        }
+    }
+}
+
+/// Where one action went through, where it was skipped up front, and where Discord
+/// refused it.
+pub struct ActionResult {
+    pub action: Action,
+    pub target_tag: String,
+    pub succeeded: Vec<GuildId>,
+    pub skipped: Vec<(GuildId, SkipReason)>,
+    pub failed: Vec<(GuildId, String)>,
+}
+
+impl ActionResult {
+    pub fn is_success(&self) -> bool {
+        !self.succeeded.is_empty()
+    }
+
+    /// The summary shown to the moderator, listing guild names and per-guild errors.
+    pub fn summary(&self, ctx: &Context, locale: &str) -> String {
+        let guild_label = |guild_id: &GuildId| {
+            ctx.cache
+                .guild(*guild_id)
+                .map(|guild| format!("{} ({})", guild.name, guild_id))
+                .unwrap_or_else(|| guild_id.to_string())
+        };
+
+        let mut summary = String::new();
+        if !self.succeeded.is_empty() {
+            let detail;
+            let count = self.succeeded.len().to_string();
+            let guilds = self
+                .succeeded
+                .iter()
+                .map(guild_label)
+                .collect::<Vec<_>>()
+                .join(", ");
+            let mut args = vec![
+                ("user", self.target_tag.as_str()),
+                ("count", count.as_str()),
+                ("guilds", guilds.as_str()),
+            ];
+            let key = match self.action {
+                Action::Kick => "moderation.kicked",
+                Action::Ban { delete_days } => {
+                    detail = delete_days.to_string();
+                    args.push(("days", &detail));
+                    "moderation.banned"
+                }
+                Action::Timeout { duration_minutes } => {
+                    detail = duration_minutes.to_string();
+                    args.push(("minutes", &detail));
+                    "moderation.timed_out"
+                }
+            };
+            summary.push_str(&i18n::t_with(key, locale, &args));
+            summary.push('\n');
+        }
+        if !self.failed.is_empty() {
+            let key = match self.action {
+                Action::Kick => "moderation.kick_failed",
+                Action::Ban { .. } => "moderation.ban_failed",
+                Action::Timeout { .. } => "moderation.timeout_failed",
+            };
+            summary.push_str(&i18n::t_with(
+                key,
+                locale,
+                &[("count", &self.failed.len().to_string())],
+            ));
+            summary.push('\n');
+            for (guild_id, error) in &self.failed {
+                summary.push_str(&i18n::t_with(
+                    "moderation.guild_failure",
+                    locale,
+                    &[("guild", &guild_label(guild_id)), ("error", error)],
+                ));
+                summary.push('\n');
+            }
+        }
+        if !self.skipped.is_empty() {
+            summary.push_str(&i18n::t_with(
+                "moderation.skipped",
+                locale,
+                &[("count", &self.skipped.len().to_string())],
+            ));
+            summary.push('\n');
+            for (guild_id, reason) in &self.skipped {
+                summary.push_str(&i18n::t_with(
+                    "moderation.guild_failure",
+                    locale,
+                    &[
+                        ("guild", &guild_label(guild_id)),
+                        ("error", &reason.describe(locale)),
+                    ],
+                ));
+                summary.push('\n');
+            }
+        }
+        if summary.is_empty() {
+            summary = match self.action {
+                Action::Ban { .. } => i18n::t("moderation.ban_no_guilds", locale),
+                _ => i18n::t_with(
+                    "moderation.not_in_any_guild",
+                    locale,
+                    &[("user", &self.target_tag)],
+                ),
+            };
+        }
+
+        summary
+    }
+}
+
+/// Spaces out Discord requests by `mod_action_delay_ms` so cross-guild and multi-user
+/// actions don't trip rate limits. Share one pacer across targets to keep the spacing.
+pub struct Pacer {
+    delay: tokio::time::Duration,
+    first_request: bool,
+}
+
+impl Pacer {
+    pub async fn new(db: &Database) -> Self {
+        let delay_ms = Settings::new(db).get_u64("mod_action_delay_ms").await;
+        Self::with_delay(tokio::time::Duration::from_millis(delay_ms))
+    }
+
+    pub fn with_delay(delay: tokio::time::Duration) -> Self {
+        Self {
+            delay,
+            first_request: true,
+        }
+    }
+
+    pub async fn wait(&mut self) {
+        if !self.first_request {
+            tokio::time::sleep(self.delay).await;
+        }
+        self.first_request = false;
+    }
+}
+
+/// Whether the bot's cached roles grant Ban Members in `guild_id`. Returns true when the
+/// guild or the bot's member isn't cached, so an incomplete cache never hides a guild.
+pub fn can_ban_in(ctx: &Context, guild_id: GuildId) -> bool {
+    let bot_id = ctx.cache.current_user().id;
+    ctx.cache
+        .guild(guild_id)
+        .and_then(|guild| guild_permissions(&guild, bot_id))
+        .is_none_or(|permissions| permissions.ban_members())
+}
+
+/// Checks the bot's cached permissions and role position against the target before an
+/// action, so mods see why it can't work instead of a raw API error. Returns `None` to go
+/// ahead, including whenever the cache is missing something needed to decide.
+pub fn precheck(
+    ctx: &Context,
+    guild_id: GuildId,
+    target_id: UserId,
+    action: Action,
+) -> Option<SkipReason> {
+    let bot_id = ctx.cache.current_user().id;
+    let guild = ctx.cache.guild(guild_id)?;
+    let permissions = guild_permissions(&guild, bot_id)?;
+
+    let (has_permission, permission_name) = match action {
+        Action::Kick => (permissions.kick_members(), "Kick Members"),
+        Action::Ban { .. } => (permissions.ban_members(), "Ban Members"),
+        Action::Timeout { .. } => (permissions.moderate_members(), "Timeout Members"),
+    };
+    if !has_permission {
+        return Some(SkipReason::MissingPermission(permission_name));
+    }
+
+    if guild.owner_id == target_id {
+        return Some(SkipReason::TargetIsOwner);
+    }
+    // Non-members can still be banned, and have no roles to compare
+    let target = guild.members.get(&target_id)?;
+    if guild.owner_id == bot_id {
+        return None;
+    }
+    if matches!(action, Action::Timeout { .. })
+        && guild_permissions(&guild, target_id).is_some_and(|p| p.administrator())
+    {
+        return Some(SkipReason::TargetIsAdmin);
+    }
+
+    let bot_position = highest_role_position(&guild, &guild.members.get(&bot_id)?.roles);
+    let target_position = highest_role_position(&guild, &target.roles);
+    if bot_position <= target_position {
+        return Some(SkipReason::RoleTooHigh);
+    }
+
+    None
+}
+
+// Guild-level permissions from roles; channel overwrites don't apply to kicks, bans or timeouts
+fn guild_permissions(guild: &Guild, user_id: UserId) -> Option<Permissions> {
+    if guild.owner_id == user_id {
+        return Some(Permissions::all());
+    }
+    let member = guild.members.get(&user_id)?;
+
+    // The @everyone role shares the guild's ID
+    let everyone = RoleId::new(guild.id.get());
+    let permissions = std::iter::once(&everyone)
+        .chain(member.roles.iter())
+        .filter_map(|role_id| guild.roles.get(role_id))
+        .fold(Permissions::empty(), |acc, role| acc | role.permissions);
+
+    if permissions.administrator() {
+        Some(Permissions::all())
+    } else {
+        Some(permissions)
+    }
+}
+
+fn highest_role_position(guild: &Guild, roles: &[RoleId]) -> u16 {
+    roles
+        .iter()
+        .filter_map(|role_id| guild.roles.get(role_id))
+        .map(|role| role.position)
+        .max()
+        .unwrap_or(0)
+}
+
+/// Kicks a user from each of `guilds` they are a cached member of.
+#[allow(clippy::too_many_arguments)]
+pub async fn execute_kick(
+    ctx: &Context,
+    db: &Database,
+    pacer: &mut Pacer,
+    actor_id: u64,
+    target_id: UserId,
+    target_tag: &str,
+    guilds: &[GuildId],
+    reason: Option<&str>,
+) -> ActionResult {
+    execute(
+        ctx,
+        db,
+        pacer,
+        Action::Kick,
+        actor_id,
+        target_id,
+        target_tag,
+        guilds,
+        reason,
+    )
+    .await
+}
+
+/// Bans a user from every one of `guilds` the bot can ban in, members or not, so they
+/// can't join later.
+#[allow(clippy::too_many_arguments)]
+pub async fn execute_ban(
+    ctx: &Context,
+    db: &Database,
+    pacer: &mut Pacer,
+    actor_id: u64,
+    target_id: UserId,
+    target_tag: &str,
+    guilds: &[GuildId],
+    delete_days: u8,
+    reason: Option<&str>,
+) -> ActionResult {
+    execute(
+        ctx,
+        db,
+        pacer,
+        Action::Ban { delete_days },
+        actor_id,
+        target_id,
+        target_tag,
+        guilds,
+        reason,
+    )
+    .await
+}
+
+/// Times a user out in each of `guilds` they are a cached member of.
+#[allow(clippy::too_many_arguments)]
+pub async fn execute_timeout(
+    ctx: &Context,
+    db: &Database,
+    pacer: &mut Pacer,
+    actor_id: u64,
+    target_id: UserId,
+    target_tag: &str,
+    guilds: &[GuildId],
+    duration_minutes: u64,
+    reason: Option<&str>,
+) -> ActionResult {
+    execute(
+        ctx,
+        db,
+        pacer,
+        Action::Timeout { duration_minutes },
+        actor_id,
+        target_id,
+        target_tag,
+        guilds,
+        reason,
+    )
+    .await
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn execute(
+    ctx: &Context,
+    db: &Database,
+    pacer: &mut Pacer,
+    action: Action,
+    actor_id: u64,
+    target_id: UserId,
+    target_tag: &str,
+    guilds: &[GuildId],
+    reason: Option<&str>,
+) -> ActionResult {
+    let mut result = ActionResult {
+        action,
+        target_tag: target_tag.to_string(),
+        succeeded: Vec::new(),
+        skipped: Vec::new(),
+        failed: Vec::new(),
+    };
+
+    let timeout_until = match action {
+        Action::Timeout { duration_minutes } => {
+            let until = i64::try_from(duration_minutes)
+                .ok()
+                .filter(|minutes| (1..=MAX_TIMEOUT_MINUTES as i64).contains(minutes))
+                .and_then(chrono::Duration::try_minutes)
+                .and_then(|duration| chrono::Utc::now().checked_add_signed(duration));
+            let Some(until) = until else {
+                result.failed.extend(guilds.iter().map(|&guild_id| {
+                    (
+                        guild_id,
+                        format!(
+                            "Timeout must be 1-{} minutes, got {}",
+                            MAX_TIMEOUT_MINUTES, duration_minutes
+                        ),
+                    )
+                }));
+                return result;
+            };
+            Some(until.to_rfc3339())
+        }
+        _ => None,
+    };
+
+    for &guild_id in guilds {
+        // Kicks and timeouts need a member to act on; bans also cover users not yet joined,
+        // but only where the bot holds Ban Members so the summary isn't padded with failures
+        let is_member = ctx
+            .cache
+            .guild(guild_id)
+            .map(|guild| guild.members.contains_key(&target_id))
+            .unwrap_or(false);
+        let allowed = match action {
+            Action::Ban { .. } => can_ban_in(ctx, guild_id),
+            _ => is_member,
+        };
+        if !allowed {
+            continue;
+        }
+
+        if let Some(reason) = precheck(ctx, guild_id, target_id, action) {
+            result.skipped.push((guild_id, reason));
+            continue;
+        }
+
+        pacer.wait().await;
+
+        let outcome = match action {
+            Action::Kick => match reason {
+                Some(reason) => {
+                    guild_id
+                        .kick_with_reason(&ctx.http, target_id, reason)
+                        .await
+                }
+                None => guild_id.kick(&ctx.http, target_id).await,
+            },
+            Action::Ban { delete_days } => match reason {
+                Some(reason) => {
+                    guild_id
+                        .ban_with_reason(&ctx.http, target_id, delete_days, reason)
+                        .await
+                }
+                None => guild_id.ban(&ctx.http, target_id, delete_days).await,
+            },
+            Action::Timeout { .. } => {
+                let mut edit_member = EditMember::new()
+                    .disable_communication_until(timeout_until.clone().unwrap_or_default());
+                if let Some(reason) = reason {
+                    edit_member = edit_member.audit_log_reason(reason);
+                }
+                guild_id
+                    .edit_member(&ctx.http, target_id, edit_member)
+                    .await
+                    .map(|_| ())
+            }
+        };
+
+        match outcome {
+            Ok(()) => {
+                let guild_name = ctx
+                    .cache
+                    .guild(guild_id)
+                    .map(|g| g.name.clone())
+                    .unwrap_or_else(|| "Unknown".to_string());
+
+                let detail = match action {
+                    Action::Kick => format!("kicked user {} ({}) from", target_tag, target_id),
+                    Action::Ban { delete_days } => format!(
+                        "banned user {} ({}) deleting {} day(s) of messages from",
+                        target_tag, target_id, delete_days
+                    ),
+                    Action::Timeout { duration_minutes } => format!(
+                        "timed out user {} ({}) for {} minutes in",
+                        target_tag, target_id, duration_minutes
+                    ),
+                };
+                info!(
+                    "[MOD ACTION] {} {} guild {} ({}) - reason: {}",
+                    actor_id,
+                    detail,
+                    guild_name,
+                    guild_id,
+                    reason.unwrap_or("none")
+                );
+                mod_actions::record(
+                    db,
+                    ModAction {
+                        action: action.name(),
+                        actor_id,
+                        target_id: target_id.get(),
+                        target_tag,
+                        guild_id: guild_id.get(),
+                        guild_name: &guild_name,
+                        reason,
+                        duration_minutes: match action {
+                            Action::Timeout { duration_minutes } => Some(duration_minutes),
+                            _ => None,
+                        },
+                    },
+                );
+                result.succeeded.push(guild_id);
+            }
+            Err(e) => result.failed.push((guild_id, e.to_string())),
+        }
+    }
+
+    result
+}
@@ -0,0 +1,372 @@
+use tracing::warn;
+
+use crate::db::Database;
+
+/// The kind of value a setting holds, used to validate `/config set`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingKind {
+    Bool,
+    Integer,
+    Unsigned,
+    /// A whole number within an inclusive range.
+    Bounded {
+        min: u64,
+        max: u64,
+    },
+    /// A non-negative number that may have a fractional part, such as `0.5`.
+    Decimal,
+    /// A Discord channel ID.
+    Channel,
+    Text,
+}
+
+impl SettingKind {
+    /// Checks a value typed by an admin, returning it in the form it is stored in.
+    pub fn validate(self, value: &str) -> Result<String, String> {
+        let value = value.trim();
+        match self {
+            SettingKind::Bool => parse_bool(value)
+                .map(|b| b.to_string())
+                .ok_or_else(|| "Expected `true` or `false`.".to_string()),
+            SettingKind::Integer => value
+                .parse::<i64>()
+                .map(|n| n.to_string())
+                .map_err(|_| "Expected a whole number.".to_string()),
+            SettingKind::Unsigned => value
+                .parse::<u64>()
+                .map(|n| n.to_string())
+                .map_err(|_| "Expected a whole number of 0 or more.".to_string()),
+            SettingKind::Bounded { min, max } => value
+                .parse::<u64>()
+                .ok()
+                .filter(|n| (min..=max).contains(n))
+                .map(|n| n.to_string())
+                .ok_or_else(|| format!("Expected a whole number from {} to {}.", min, max)),
+            SettingKind::Decimal => parse_decimal(value)
+                .map(|n| n.to_string())
+                .ok_or_else(|| "Expected a number of 0 or more, such as `0.5`.".to_string()),
+            SettingKind::Channel => value
+                .trim_start_matches("<#")
+                .trim_end_matches('>')
+                .parse::<u64>()
+                .ok()
+                .filter(|id| *id > 0)
+                .map(|id| id.to_string())
+                .ok_or_else(|| "Expected a channel mention or ID.".to_string()),
+            SettingKind::Text => Ok(value.to_string()),
+        }
+    }
+}
+
+/// A `system_settings` key that `/config set` is allowed to write.
+pub struct SettingInfo {
+    pub key: &'static str,
+    pub summary: &'static str,
+    pub kind: SettingKind,
+    /// Value used when the setting is missing or can't be parsed.
+    pub default: Option<&'static str>,
+    /// Also accepted as `{key}_{guild_id}` to override the value for one guild.
+    pub per_guild: bool,
+}
+
+/// Every setting an admin may change. Bookkeeping keys the bot writes itself
+/// (`last_startup`, `digest_last_posted`, `meme_buttons_*`, ...) are left out so a
+/// typo can't create an orphan setting or clobber internal state.
+pub const KNOWN_SETTINGS: &[SettingInfo] = &[
+    SettingInfo {
+        key: "cache_media",
+        summary: "Cache attachments posted in servers (true/false)",
+        kind: SettingKind::Bool,
+        default: Some("true"),
+        per_guild: false,
+    },
+    SettingInfo {
+        key: "media_cache_max_bytes",
+        summary: "Total size cap for the media cache in bytes",
+        kind: SettingKind::Unsigned,
+        default: Some("10737418240"),
+        per_guild: false,
+    },
+    SettingInfo {
+        key: "max_attachment_bytes",
+        summary: "Largest single file downloaded for the cache or a meme, in bytes",
+        // Up to 500 MiB, Discord's largest upload size
+        kind: SettingKind::Bounded {
+            min: 1,
+            max: 500 * 1024 * 1024,
+        },
+        default: Some("26214400"),
+        per_guild: false,
+    },
+    SettingInfo {
+        key: "mod_action_delay_ms",
+        summary: "Pause between per-guild moderation API calls, in milliseconds",
+        kind: SettingKind::Unsigned,
+        default: Some("250"),
+        per_guild: false,
+    },
+    SettingInfo {
+        key: "mod_action_webhook_url",
+        summary: "Webhook that successful moderation actions are POSTed to",
+        kind: SettingKind::Text,
+        default: None,
+        per_guild: false,
+    },
+    SettingInfo {
+        key: "mod_alert_channel",
+        summary: "Channel ID every mod alert is sent to",
+        kind: SettingKind::Channel,
+        default: None,
+        per_guild: false,
+    },
+    SettingInfo {
+        key: "min_account_age_days",
+        summary: "Flag joining accounts younger than this many days (0 = off)",
+        kind: SettingKind::Integer,
+        default: Some("0"),
+        per_guild: false,
+    },
+    SettingInfo {
+        key: "full_member_sync",
+        summary: "Fetch full member lists from the API on connect (true/false)",
+        kind: SettingKind::Bool,
+        default: Some("false"),
+        per_guild: false,
+    },
+    SettingInfo {
+        key: "welcome_channel",
+        summary: "Channel ID new members are welcomed in",
+        kind: SettingKind::Channel,
+        default: None,
+        per_guild: true,
+    },
+    SettingInfo {
+        key: "welcome_template",
+        summary: "Welcome message with {user} and {guild} placeholders",
+        kind: SettingKind::Text,
+        default: None,
+        per_guild: true,
+    },
+    SettingInfo {
+        key: "digest_channel",
+        summary: "Channel ID the weekly digest is posted to",
+        kind: SettingKind::Channel,
+        default: None,
+        per_guild: false,
+    },
+    SettingInfo {
+        key: "digest_enabled",
+        summary: "Post the weekly digest (true/false)",
+        kind: SettingKind::Bool,
+        default: Some("false"),
+        per_guild: false,
+    },
+    SettingInfo {
+        key: "global_promote_threshold",
+        summary: "Net votes a global watchlist item needs to be announced (0 = off)",
+        kind: SettingKind::Integer,
+        default: Some("10"),
+        per_guild: false,
+    },
+    SettingInfo {
+        key: "giphy_cache_target",
+        summary: "GIFs to keep cached per GIPHY search term",
+        kind: SettingKind::Unsigned,
+        default: Some("25"),
+        per_guild: false,
+    },
+    SettingInfo {
+        key: "snort_cooldown_seconds",
+        summary: "Global cooldown for /snort, in seconds",
+        kind: SettingKind::Unsigned,
+        default: Some("30"),
+        per_guild: false,
+    },
+    SettingInfo {
+        key: "public_user_recommendations",
+        summary: "Let anyone use /recommendations by on other members (false = whitelisted only)",
+        kind: SettingKind::Bool,
+        default: Some("true"),
+        per_guild: false,
+    },
+    SettingInfo {
+        key: "spam_max_messages",
+        summary:
+            "Messages a user may send within spam_window_seconds before mods are alerted (0 = off)",
+        kind: SettingKind::Unsigned,
+        default: Some("8"),
+        per_guild: false,
+    },
+    SettingInfo {
+        key: "spam_window_seconds",
+        summary: "Length of the sliding window used for spam detection, in seconds",
+        kind: SettingKind::Unsigned,
+        default: Some("10"),
+        per_guild: false,
+    },
+    SettingInfo {
+        key: "autotimeout_spam",
+        summary: "Also time out users flagged for spam (true/false)",
+        kind: SettingKind::Bool,
+        default: Some("false"),
+        per_guild: false,
+    },
+    SettingInfo {
+        key: "spam_timeout_minutes",
+        summary: "Timeout length for autotimeout_spam, in minutes (1-40320, Discord's 28-day cap)",
+        kind: SettingKind::Bounded {
+            min: 1,
+            max: crate::moderation::MAX_TIMEOUT_MINUTES,
+        },
+        default: Some("10"),
+        per_guild: false,
+    },
+    SettingInfo {
+        key: "snort_meme_dir",
+        summary: "Folder of local /snort memes; a {guild_id} subfolder overrides it per server",
+        kind: SettingKind::Text,
+        default: Some("memes/snort"),
+        per_guild: false,
+    },
+    SettingInfo {
+        key: "detector_confidence_anime",
+        summary: "Multiplier for detected anime confidence (1.0 = unchanged, 0 = don't record)",
+        kind: SettingKind::Decimal,
+        default: Some("1.0"),
+        per_guild: false,
+    },
+    SettingInfo {
+        key: "detector_confidence_tv_show",
+        summary: "Multiplier for detected TV show confidence (1.0 = unchanged, 0 = don't record)",
+        kind: SettingKind::Decimal,
+        default: Some("1.0"),
+        per_guild: false,
+    },
+    SettingInfo {
+        key: "detector_confidence_game",
+        summary: "Multiplier for detected game confidence (1.0 = unchanged, 0 = don't record)",
+        kind: SettingKind::Decimal,
+        default: Some("1.0"),
+        per_guild: false,
+    },
+    SettingInfo {
+        key: "detector_confidence_youtube",
+        summary: "Multiplier for detected YouTube confidence (1.0 = unchanged, 0 = don't record)",
+        kind: SettingKind::Decimal,
+        default: Some("1.0"),
+        per_guild: false,
+    },
+    SettingInfo {
+        key: "steam_name_lookup",
+        summary: "Look up game names for Steam store links via the Steam store API (true/false)",
+        kind: SettingKind::Bool,
+        default: Some("true"),
+        per_guild: false,
+    },
+    SettingInfo {
+        key: "gif_cooldown_seconds",
+        summary: "Per-user cooldown for /gif, in seconds (0 = off)",
+        kind: SettingKind::Unsigned,
+        default: Some("10"),
+        per_guild: false,
+    },
+    SettingInfo {
+        key: "meme_cooldown_seconds",
+        summary: "Per-user cooldown for /meme random, in seconds (0 = off)",
+        kind: SettingKind::Unsigned,
+        default: Some("10"),
+        per_guild: false,
+    },
+];
+
+/// Finds the catalogue entry for `key`, accepting `{key}_{guild_id}` for per-guild settings.
+pub fn lookup(key: &str) -> Option<&'static SettingInfo> {
+    KNOWN_SETTINGS.iter().find(|setting| {
+        setting.key == key
+            || (setting.per_guild
+                && key
+                    .strip_prefix(setting.key)
+                    .and_then(|rest| rest.strip_prefix('_'))
+                    .is_some_and(|id| !id.is_empty() && id.bytes().all(|b| b.is_ascii_digit())))
+    })
+}
+
+/// Whether a setting's value should be hidden when settings are displayed.
+pub fn is_secret(key: &str) -> bool {
+    let key = key.to_lowercase();
+    ["token", "key", "secret", "password", "webhook"]
+        .iter()
+        .any(|word| key.contains(word))
+}
+
+/// Reads a boolean the way every setting does: `true`/`1`/`yes`/`on` or
+/// `false`/`0`/`no`/`off`, ignoring case.
+pub fn parse_bool(value: &str) -> Option<bool> {
+    match value.trim().to_lowercase().as_str() {
+        "true" | "1" | "yes" | "on" => Some(true),
+        "false" | "0" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+/// Reads a non-negative, finite number such as `1`, `0.75` or `1.5`.
+pub fn parse_decimal(value: &str) -> Option<f64> {
+    value
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|n| n.is_finite() && *n >= 0.0)
+}
+
+/// Typed reads of `system_settings`. Missing, unreadable or unparsable values fall back
+/// to the default in `KNOWN_SETTINGS`, so every caller agrees on what a setting means.
+pub struct Settings<'a> {
+    db: &'a Database,
+}
+
+impl<'a> Settings<'a> {
+    pub fn new(db: &'a Database) -> Self {
+        Self { db }
+    }
+
+    async fn read<T>(&self, key: &str, parse: impl Fn(&str) -> Option<T>) -> Option<T> {
+        let stored = match self.db.get_setting(key).await {
+            Ok(value) => value,
+            Err(e) => {
+                warn!("Failed to read setting {}: {}", key, e);
+                None
+            }
+        };
+
+        stored.as_deref().and_then(&parse).or_else(|| {
+            lookup(key)
+                .and_then(|setting| setting.default)
+                .and_then(parse)
+        })
+    }
+
+    pub async fn get_bool(&self, key: &str) -> bool {
+        self.read(key, parse_bool).await.unwrap_or(false)
+    }
+
+    pub async fn get_u64(&self, key: &str) -> u64 {
+        self.read(key, |v| v.trim().parse().ok()).await.unwrap_or(0)
+    }
+
+    pub async fn get_i64(&self, key: &str) -> i64 {
+        self.read(key, |v| v.trim().parse().ok()).await.unwrap_or(0)
+    }
+
+    pub async fn get_f64(&self, key: &str) -> f64 {
+        self.read(key, parse_decimal).await.unwrap_or(0.0)
+    }
+
+    /// Reads a text setting, treating a blank value as unset.
+    pub async fn get_text(&self, key: &str) -> String {
+        self.read(key, |v| {
+            Some(v.trim().to_string()).filter(|v| !v.is_empty())
+        })
+        .await
+        .unwrap_or_default()
+    }
+}
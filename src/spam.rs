@@ -0,0 +1,254 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How often idle windows are swept out of the tracker.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Sliding-window message counter per (guild, user), kept in memory only.
+pub struct SpamTracker {
+    windows: Mutex<HashMap<(u64, u64), VecDeque<Instant>>>,
+    last_sweep: Mutex<Instant>,
+}
+
+impl SpamTracker {
+    pub fn new() -> Self {
+        Self {
+            windows: Mutex::new(HashMap::new()),
+            last_sweep: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Records a message and returns how many the user sent within `window` if that is
+    /// more than `max_messages`. The user's window is cleared when it trips, so one burst
+    /// produces one hit rather than one per further message.
+    pub fn record(
+        &self,
+        guild_id: u64,
+        user_id: u64,
+        max_messages: usize,
+        window: Duration,
+    ) -> Option<usize> {
+        let now = Instant::now();
+        let Ok(mut windows) = self.windows.lock() else {
+            return None;
+        };
+
+        self.sweep(&mut windows, now, window);
+
+        let timestamps = windows.entry((guild_id, user_id)).or_default();
+        timestamps.push_back(now);
+        while timestamps
+            .front()
+            .is_some_and(|sent| now.duration_since(*sent) > window)
+        {
+            timestamps.pop_front();
+        }
+
+        let count = timestamps.len();
+        if count > max_messages {
+            windows.remove(&(guild_id, user_id));
+            Some(count)
+        } else {
+            None
+        }
+    }
+
+    // Drop users with nothing inside the window so quiet members don't accumulate
+    fn sweep(
+        &self,
+        windows: &mut HashMap<(u64, u64), VecDeque<Instant>>,
+        now: Instant,
+        window: Duration,
+    ) {
+        if !sweep_due(&self.last_sweep, now) {
+            return;
+        }
+
+        windows.retain(|_, timestamps| {
+            timestamps
+                .back()
+                .is_some_and(|sent| now.duration_since(*sent) <= window)
+        });
+    }
+}
+
+/// Per (guild, user): content hash, channel and time of each recent message.
+type RecentContent = HashMap<(u64, u64), Vec<(u64, u64, Instant)>>;
+
+/// Recent message contents per (guild, user), hashed, with the channel each was posted in.
+/// Catches the same text pasted across channels, which per-channel rate limits miss.
+pub struct CopypastaTracker {
+    recent: Mutex<RecentContent>,
+    last_sweep: Mutex<Instant>,
+}
+
+impl CopypastaTracker {
+    pub fn new() -> Self {
+        Self {
+            recent: Mutex::new(HashMap::new()),
+            last_sweep: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Records a message and, once the same normalized content has been posted in at least
+    /// `min_channels` different channels within `window`, returns those channels. The
+    /// matching entries are then forgotten so one paste run raises one alert.
+    pub fn record(
+        &self,
+        guild_id: u64,
+        user_id: u64,
+        channel_id: u64,
+        content: &str,
+        min_channels: usize,
+        window: Duration,
+    ) -> Option<Vec<u64>> {
+        let now = Instant::now();
+        let hash = content_hash(content);
+        let Ok(mut recent) = self.recent.lock() else {
+            return None;
+        };
+
+        if sweep_due(&self.last_sweep, now) {
+            recent.retain(|_, entries| {
+                entries.retain(|(_, _, sent)| now.duration_since(*sent) <= window);
+                !entries.is_empty()
+            });
+        }
+
+        let entries = recent.entry((guild_id, user_id)).or_default();
+        entries.retain(|(_, _, sent)| now.duration_since(*sent) <= window);
+        entries.push((hash, channel_id, now));
+
+        let mut channels: Vec<u64> = entries
+            .iter()
+            .filter(|(entry_hash, _, _)| *entry_hash == hash)
+            .map(|(_, channel, _)| *channel)
+            .collect();
+        channels.sort_unstable();
+        channels.dedup();
+
+        if channels.len() >= min_channels {
+            entries.retain(|(entry_hash, _, _)| *entry_hash != hash);
+            Some(channels)
+        } else {
+            None
+        }
+    }
+}
+
+/// Lowercases and collapses whitespace so trivially reformatted pastes still match.
+pub fn normalize_content(content: &str) -> String {
+    content
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+fn content_hash(content: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    normalize_content(content).hash(&mut hasher);
+    hasher.finish()
+}
+
+// True at most once per SWEEP_INTERVAL, so idle entries are pruned without a background task
+fn sweep_due(last_sweep: &Mutex<Instant>, now: Instant) -> bool {
+    let Ok(mut last_sweep) = last_sweep.lock() else {
+        return false;
+    };
+    if now.duration_since(*last_sweep) < SWEEP_INTERVAL {
+        return false;
+    }
+    *last_sweep = now;
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOW: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn spam_trips_only_above_the_threshold() {
+        let tracker = SpamTracker::new();
+        for _ in 0..3 {
+            assert_eq!(tracker.record(1, 10, 3, WINDOW), None);
+        }
+        assert_eq!(tracker.record(1, 10, 3, WINDOW), Some(4));
+    }
+
+    #[test]
+    fn spam_window_resets_after_a_trip() {
+        let tracker = SpamTracker::new();
+        for _ in 0..3 {
+            tracker.record(1, 10, 2, WINDOW);
+        }
+        // The burst above tripped once; counting starts over
+        assert_eq!(tracker.record(1, 10, 2, WINDOW), None);
+        assert_eq!(tracker.record(1, 10, 2, WINDOW), None);
+        assert_eq!(tracker.record(1, 10, 2, WINDOW), Some(3));
+    }
+
+    #[test]
+    fn spam_forgets_messages_outside_the_window() {
+        let tracker = SpamTracker::new();
+        let window = Duration::from_millis(50);
+        tracker.record(1, 10, 2, window);
+        tracker.record(1, 10, 2, window);
+        std::thread::sleep(Duration::from_millis(80));
+        assert_eq!(tracker.record(1, 10, 2, window), None);
+    }
+
+    #[test]
+    fn spam_counts_each_guild_and_user_separately() {
+        let tracker = SpamTracker::new();
+        tracker.record(1, 10, 2, WINDOW);
+        tracker.record(1, 10, 2, WINDOW);
+        assert_eq!(tracker.record(1, 11, 2, WINDOW), None);
+        assert_eq!(tracker.record(2, 10, 2, WINDOW), None);
+        assert_eq!(tracker.record(1, 10, 2, WINDOW), Some(3));
+    }
+
+    #[test]
+    fn copypasta_needs_distinct_channels() {
+        let tracker = CopypastaTracker::new();
+        let paste = "buy cheap followers now";
+        assert_eq!(tracker.record(1, 10, 100, paste, 3, WINDOW), None);
+        // Repeats in the same channel don't add to the count
+        assert_eq!(tracker.record(1, 10, 100, paste, 3, WINDOW), None);
+        assert_eq!(tracker.record(1, 10, 101, paste, 3, WINDOW), None);
+        assert_eq!(
+            tracker.record(1, 10, 102, paste, 3, WINDOW),
+            Some(vec![100, 101, 102])
+        );
+    }
+
+    #[test]
+    fn copypasta_resets_after_a_trip() {
+        let tracker = CopypastaTracker::new();
+        let paste = "join my server";
+        for channel in [100, 101] {
+            tracker.record(1, 10, channel, paste, 2, WINDOW);
+        }
+        assert_eq!(tracker.record(1, 10, 102, paste, 2, WINDOW), None);
+        assert_eq!(
+            tracker.record(1, 10, 103, paste, 2, WINDOW),
+            Some(vec![102, 103])
+        );
+    }
+
+    #[test]
+    fn copypasta_matches_reformatted_text_only() {
+        let tracker = CopypastaTracker::new();
+        tracker.record(1, 10, 100, "Free  Nitro\nHere", 2, WINDOW);
+        assert_eq!(tracker.record(1, 10, 101, "other text", 2, WINDOW), None);
+        assert_eq!(
+            tracker.record(1, 10, 102, "free nitro here", 2, WINDOW),
+            Some(vec![100, 102])
+        );
+    }
+}
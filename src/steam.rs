@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use anyhow::Result;
+use serde::Deserialize;
+use tracing::warn;
+
+use crate::db::Database;
+use crate::media_detector::MediaRecommendation;
+use crate::settings::Settings;
+
+const APP_DETAILS_URL: &str = "https://store.steampowered.com/api/appdetails";
+const STEAM_TIMEOUT_SECS: u64 = 10;
+
+// The store API keys its response by the requested app ID
+#[derive(Debug, Deserialize)]
+struct AppDetails {
+    success: bool,
+    data: Option<AppData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AppData {
+    name: String,
+}
+
+/// Replaces the slug or "Steam App {id}" title of Steam store links with the store name,
+/// using `steam_app_cache` before asking the Steam store API. Titles are left alone when
+/// `steam_name_lookup` is off or the lookup fails.
+pub async fn resolve_titles(db: &Database, recommendations: &mut [MediaRecommendation]) {
+    if !recommendations.iter().any(|rec| rec.steam_app_id.is_some()) {
+        return;
+    }
+    if !Settings::new(db).get_bool("steam_name_lookup").await {
+        return;
+    }
+
+    for rec in recommendations.iter_mut() {
+        let Some(app_id) = rec.steam_app_id else {
+            continue;
+        };
+
+        match app_name(db, app_id).await {
+            Ok(Some(name)) => rec.title = name,
+            Ok(None) => {}
+            Err(e) => warn!("Failed to resolve Steam app {}: {}", app_id, e),
+        }
+    }
+}
+
+async fn app_name(db: &Database, app_id: u64) -> Result<Option<String>> {
+    if let Some(name) = db.get_steam_app_name(app_id).await? {
+        return Ok(Some(name));
+    }
+
+    let Some(name) = fetch_app_name(app_id).await? else {
+        return Ok(None);
+    };
+    db.set_steam_app_name(app_id, &name).await?;
+
+    Ok(Some(name))
+}
+
+/// Asks the public store API for an app's name. `None` when Steam doesn't know the ID.
+async fn fetch_app_name(app_id: u64) -> Result<Option<String>> {
+    let response = reqwest::Client::new()
+        .get(APP_DETAILS_URL)
+        .query(&[
+            ("appids", app_id.to_string().as_str()),
+            ("filters", "basic"),
+        ])
+        .timeout(Duration::from_secs(STEAM_TIMEOUT_SECS))
+        .send()
+        .await?;
+
+    if !response.status().is_success() {
+        anyhow::bail!("Steam store API error: {}", response.status());
+    }
+
+    let mut details = response.json::<HashMap<String, AppDetails>>().await?;
+
+    Ok(details
+        .remove(&app_id.to_string())
+        .filter(|details| details.success)
+        .and_then(|details| details.data)
+        .map(|data| data.name.trim().to_string())
+        .filter(|name| !name.is_empty()))
+}
@@ -0,0 +1,86 @@
+use anyhow::Result;
+use reqwest::Client;
+use serde::Deserialize;
+use std::env;
+use std::time::Duration;
+
+const POSTER_BASE_URL: &str = "https://image.tmdb.org/t/p/w500";
+const TMDB_TIMEOUT_SECS: u64 = 5;
+
+#[derive(Debug, Clone, Deserialize)]
+struct TmdbSearchResponse {
+    results: Vec<TmdbResult>,
+}
+
+// Movies use `title`/`release_date`, TV shows use `name`/`first_air_date`
+#[derive(Debug, Clone, Deserialize)]
+struct TmdbResult {
+    title: Option<String>,
+    name: Option<String>,
+    release_date: Option<String>,
+    first_air_date: Option<String>,
+    poster_path: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TmdbMatch {
+    pub title: String,
+    pub year: Option<String>,
+    pub poster_url: Option<String>,
+}
+
+pub struct TmdbClient {
+    client: Client,
+    api_key: String,
+}
+
+impl TmdbClient {
+    pub fn new() -> Result<Self> {
+        let api_key = env::var("TMDB_API_KEY")?;
+        let client = Client::builder()
+            .timeout(Duration::from_secs(TMDB_TIMEOUT_SECS))
+            .build()?;
+
+        Ok(Self { client, api_key })
+    }
+
+    /// Looks up the best TMDB match for a `movie` or `tv_show` title.
+    pub async fn lookup(&self, media_type: &str, query: &str) -> Result<Option<TmdbMatch>> {
+        let url = match media_type {
+            "movie" => "https://api.themoviedb.org/3/search/movie",
+            "tv_show" => "https://api.themoviedb.org/3/search/tv",
+            _ => return Ok(None),
+        };
+
+        let response = self
+            .client
+            .get(url)
+            .query(&[("api_key", self.api_key.as_str()), ("query", query)])
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            anyhow::bail!("TMDB API error: {}", response.status());
+        }
+
+        let search = response.json::<TmdbSearchResponse>().await?;
+
+        Ok(search.results.into_iter().next().and_then(|result| {
+            let title = result.title.or(result.name)?;
+            let year = result
+                .release_date
+                .or(result.first_air_date)
+                .and_then(|date| date.get(..4).map(|y| y.to_string()))
+                .filter(|y| !y.is_empty());
+            let poster_url = result
+                .poster_path
+                .map(|path| format!("{}{}", POSTER_BASE_URL, path));
+
+            Some(TmdbMatch {
+                title,
+                year,
+                poster_url,
+            })
+        }))
+    }
+}